@@ -0,0 +1,171 @@
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use cargo_toml::Manifest;
+use toml::Value;
+
+use crate::{
+    config::{self, Origin},
+    targets::Target,
+};
+
+/// File names Cargo produces for the library and binaries of `manifest` on `target`
+pub fn file_names(manifest: &Manifest, target: &Target) -> Vec<String> {
+    let mut files = Vec::new();
+
+    if let Some(lib) = &manifest.lib {
+        let name = lib.name.clone().unwrap_or_else(|| {
+            manifest
+                .package
+                .as_ref()
+                .map(|p| p.name().replace('-', "_"))
+                .unwrap_or_default()
+        });
+
+        let crate_types: Vec<&str> = if lib.proc_macro {
+            vec!["proc-macro"]
+        } else {
+            lib.crate_type.iter().map(String::as_str).collect()
+        };
+
+        for crate_type in crate_types {
+            files.extend(lib_file_names(&name, crate_type, target));
+        }
+    }
+
+    for bin in &manifest.bin {
+        if let Some(name) = &bin.name {
+            files.extend(bin_file_names(name, target));
+        }
+    }
+
+    files
+}
+
+fn lib_file_names(name: &str, crate_type: &str, target: &Target) -> Vec<String> {
+    match crate_type {
+        "lib" | "rlib" => vec![format!("lib{name}.rlib")],
+        "staticlib" if target.is_windows() && target.env == "msvc" => vec![format!("{name}.lib")],
+        "staticlib" => vec![format!("lib{name}.a")],
+        "dylib" | "cdylib" | "proc-macro" => dylib_file_names(name, target),
+        _ => Vec::new(),
+    }
+}
+
+fn dylib_file_names(name: &str, target: &Target) -> Vec<String> {
+    if target.is_windows() {
+        let import_lib = match target.env {
+            "msvc" => format!("{name}.dll.lib"),
+            _ => format!("lib{name}.dll.a"),
+        };
+        vec![format!("{name}.dll"), import_lib]
+    } else if target.is_apple() {
+        vec![format!("lib{name}.dylib")]
+    } else if target.is_wasm() {
+        vec![format!("{name}.wasm")]
+    } else {
+        vec![format!("lib{name}.so")]
+    }
+}
+
+fn bin_file_names(name: &str, target: &Target) -> Vec<String> {
+    if target.is_windows() {
+        vec![format!("{name}.exe")]
+    } else if target.os == "emscripten" {
+        vec![format!("{name}.js"), format!("{name}.wasm")]
+    } else if target.is_wasm() {
+        vec![format!("{name}.wasm")]
+    } else {
+        vec![name.to_owned()]
+    }
+}
+
+/// Cargo names the output directory of the `dev` and `test` profiles `debug`
+pub fn profile_dir(profile: &str) -> &str {
+    match profile {
+        "dev" | "test" => "debug",
+        "bench" => "release",
+        other => other,
+    }
+}
+
+/// The target directory Cargo builds into for the workspace in `workspace_dir`, from
+/// `CARGO_TARGET_DIR` or `build.target-dir` and `<workspace>/target` otherwise.
+/// A relative `build.target-dir` is relative to the directory containing its `.cargo` dir.
+pub fn target_dir(workspace_dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    if let Some(dir) = std::env::var_os("CARGO_TARGET_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+
+    let root = config::load(workspace_dir)?;
+    match config::get(&root, "build.target-dir") {
+        Ok(config::Node::Leaf(Value::String(dir), Origin::File(file))) => {
+            let base = file
+                .parent()
+                .and_then(Path::parent)
+                .unwrap_or(workspace_dir);
+            Ok(base.join(dir))
+        }
+        Ok(config::Node::Leaf(Value::String(dir), Origin::Env(_))) => Ok(PathBuf::from(dir)),
+        Ok(_) => Err("`build.target-dir` must be a string".into()),
+        Err(_) => Ok(workspace_dir.join("target")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn names(crate_type: &str, triple: &str) -> Vec<String> {
+        lib_file_names("foo_bar", crate_type, &triple.parse().unwrap())
+    }
+
+    #[test]
+    fn lib_file_names_ok() {
+        assert_eq!(
+            names("lib", "x86_64-unknown-linux-gnu"),
+            ["libfoo_bar.rlib"]
+        );
+        assert_eq!(
+            names("cdylib", "x86_64-unknown-linux-gnu"),
+            ["libfoo_bar.so"]
+        );
+        assert_eq!(
+            names("cdylib", "aarch64-apple-darwin"),
+            ["libfoo_bar.dylib"]
+        );
+        assert_eq!(
+            names("cdylib", "x86_64-pc-windows-msvc"),
+            ["foo_bar.dll", "foo_bar.dll.lib"]
+        );
+        assert_eq!(
+            names("cdylib", "x86_64-pc-windows-gnu"),
+            ["foo_bar.dll", "libfoo_bar.dll.a"]
+        );
+        assert_eq!(names("cdylib", "wasm32-unknown-unknown"), ["foo_bar.wasm"]);
+        assert_eq!(
+            names("staticlib", "x86_64-pc-windows-msvc"),
+            ["foo_bar.lib"]
+        );
+        assert_eq!(
+            names("staticlib", "x86_64-pc-windows-gnu"),
+            ["libfoo_bar.a"]
+        );
+        assert_eq!(names("staticlib", "x86_64-apple-darwin"), ["libfoo_bar.a"]);
+    }
+
+    #[test]
+    fn bin_file_names_ok() {
+        let names = |triple: &str| bin_file_names("foo-bar", &triple.parse().unwrap());
+
+        assert_eq!(names("x86_64-unknown-linux-gnu"), ["foo-bar"]);
+        assert_eq!(names("x86_64-pc-windows-msvc"), ["foo-bar.exe"]);
+        assert_eq!(names("wasm32-wasip1"), ["foo-bar.wasm"]);
+        assert_eq!(
+            names("wasm32-unknown-emscripten"),
+            ["foo-bar.js", "foo-bar.wasm"]
+        );
+    }
+}
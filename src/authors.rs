@@ -0,0 +1,74 @@
+use crate::json::Json;
+
+/// An entry of `package.authors`, usually written as `Name <email>`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Author {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+impl Author {
+    pub fn parse(input: &str) -> Self {
+        let input = input.trim();
+
+        let (name, email) = match (input.find('<'), input.rfind('>')) {
+            (Some(start), Some(end)) if start < end => {
+                (&input[..start], Some(&input[start + 1..end]))
+            }
+            _ if !input.contains(char::is_whitespace) && input.contains('@') => ("", Some(input)),
+            _ => (input, None),
+        };
+
+        let non_empty = |s: &str| Some(s.trim().to_owned()).filter(|s| !s.is_empty());
+
+        Self {
+            name: non_empty(name),
+            email: email.and_then(non_empty),
+        }
+    }
+
+    pub fn to_json(&self) -> Json {
+        Json::object([
+            ("name", self.name.clone().into()),
+            ("email", self.email.clone().into()),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn author(name: Option<&str>, email: Option<&str>) -> Author {
+        Author {
+            name: name.map(ToOwned::to_owned),
+            email: email.map(ToOwned::to_owned),
+        }
+    }
+
+    #[test]
+    fn parse_ok() {
+        let expected = author(Some("John Doe"), Some("john-doe@abc.com"));
+
+        assert_eq!(Author::parse("John Doe<john-doe@abc.com>"), expected);
+        assert_eq!(Author::parse("John Doe <john-doe@abc.com>"), expected);
+        assert_eq!(
+            Author::parse("  John Doe   < john-doe@abc.com > "),
+            expected
+        );
+
+        assert_eq!(
+            Author::parse("Nice Folks"),
+            author(Some("Nice Folks"), None)
+        );
+        assert_eq!(Author::parse("John Doe <>"), author(Some("John Doe"), None));
+        assert_eq!(
+            Author::parse("<john-doe@abc.com>"),
+            author(None, Some("john-doe@abc.com"))
+        );
+        assert_eq!(
+            Author::parse("john-doe@abc.com"),
+            author(None, Some("john-doe@abc.com"))
+        );
+    }
+}
@@ -0,0 +1,296 @@
+use std::{fmt, str::FromStr};
+
+use crate::targets::Target;
+
+/// A `cfg(...)` predicate as used in `[target.'cfg(...)'.dependencies]`
+#[derive(Debug, PartialEq, Clone)]
+pub enum Cfg {
+    Name(String),
+    KeyPair(String, String),
+    All(Vec<Cfg>),
+    Any(Vec<Cfg>),
+    Not(Box<Cfg>),
+}
+
+/// The key of a `[target.<platform>]` table: a target triple or a `cfg(...)` expression
+#[derive(Debug, PartialEq, Clone)]
+pub enum Platform {
+    Triple(String),
+    Cfg(Cfg),
+}
+
+/// A set cfg option, either `name` or `key = "value"`
+#[derive(Debug, PartialEq, Clone)]
+pub struct CfgValue {
+    pub key: String,
+    pub value: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct InvalidCfg(pub String);
+
+impl fmt::Display for InvalidCfg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid cfg expression `{}`", self.0)
+    }
+}
+
+impl std::error::Error for InvalidCfg {}
+
+impl FromStr for Platform {
+    type Err = InvalidCfg;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.strip_prefix("cfg(").and_then(|s| s.strip_suffix(')')) {
+            Some(inner) => {
+                let tokens = tokenize(inner).ok_or_else(|| InvalidCfg(s.to_owned()))?;
+                let mut parser = Parser { tokens, pos: 0 };
+                let cfg = parser.cfg().ok_or_else(|| InvalidCfg(s.to_owned()))?;
+                match parser.pos == parser.tokens.len() {
+                    true => Ok(Self::Cfg(cfg)),
+                    false => Err(InvalidCfg(s.to_owned())),
+                }
+            }
+            None => Ok(Self::Triple(s.to_owned())),
+        }
+    }
+}
+
+impl FromStr for CfgValue {
+    type Err = InvalidCfg;
+
+    /// `name`, `key=value` or `key="value"`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = match s.split_once('=') {
+            Some((key, value)) => (key.trim(), Some(value.trim().trim_matches('"').to_owned())),
+            None => (s.trim(), None),
+        };
+        match !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            true => Ok(Self {
+                key: key.to_owned(),
+                value,
+            }),
+            false => Err(InvalidCfg(s.to_owned())),
+        }
+    }
+}
+
+impl Platform {
+    pub fn matches(&self, target: &Target, cfgs: &[CfgValue]) -> bool {
+        match self {
+            Self::Triple(triple) => triple == target.triple,
+            Self::Cfg(cfg) => cfg.matches(cfgs),
+        }
+    }
+}
+
+impl Cfg {
+    pub fn matches(&self, cfgs: &[CfgValue]) -> bool {
+        match self {
+            Self::Name(name) => cfgs.iter().any(|c| &c.key == name && c.value.is_none()),
+            Self::KeyPair(key, value) => cfgs
+                .iter()
+                .any(|c| &c.key == key && c.value.as_ref() == Some(value)),
+            Self::All(cfgs_) => cfgs_.iter().all(|c| c.matches(cfgs)),
+            Self::Any(cfgs_) => cfgs_.iter().any(|c| c.matches(cfgs)),
+            Self::Not(cfg) => !cfg.matches(cfgs),
+        }
+    }
+}
+
+/// Built-in cfg values rustc sets for `target`
+pub fn target_cfgs(target: &Target) -> Vec<CfgValue> {
+    let pair = |key: &str, value: &str| CfgValue {
+        key: key.to_owned(),
+        value: Some(value.to_owned()),
+    };
+
+    let mut cfgs = vec![
+        pair("target_arch", target.arch),
+        pair("target_os", target.os),
+        pair("target_env", target.env),
+        pair("target_vendor", target.vendor()),
+        pair("target_pointer_width", target.pointer_width()),
+        pair("target_endian", target.endian()),
+    ];
+
+    for family in &target.families {
+        cfgs.push(pair("target_family", family));
+        if matches!(*family, "unix" | "windows") {
+            cfgs.push(CfgValue {
+                key: family.to_string(),
+                value: None,
+            });
+        }
+    }
+
+    cfgs
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Open,
+    Close,
+    Comma,
+    Equals,
+}
+
+fn tokenize(s: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            ',' => tokens.push(Token::Comma),
+            '=' => tokens.push(Token::Equals),
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        c => value.push(c),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut ident = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    ident.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            _ => return None,
+        }
+    }
+
+    Some(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn cfg(&mut self) -> Option<Cfg> {
+        let Token::Ident(ident) = self.next()? else {
+            return None;
+        };
+
+        match (ident.as_str(), self.peek()) {
+            ("all" | "any" | "not", Some(Token::Open)) => {
+                self.next();
+                let mut list = Vec::new();
+                while self.peek() != Some(&Token::Close) {
+                    list.push(self.cfg()?);
+                    match self.peek()? {
+                        Token::Comma => {
+                            self.next();
+                        }
+                        Token::Close => {}
+                        _ => return None,
+                    }
+                }
+                self.next();
+
+                match ident.as_str() {
+                    "all" => Some(Cfg::All(list)),
+                    "any" => Some(Cfg::Any(list)),
+                    _ if list.len() == 1 => Some(Cfg::Not(Box::new(list.remove(0)))),
+                    _ => None,
+                }
+            }
+            (_, Some(Token::Equals)) => {
+                self.next();
+                match self.next()? {
+                    Token::Str(value) => Some(Cfg::KeyPair(ident, value)),
+                    _ => None,
+                }
+            }
+            _ => Some(Cfg::Name(ident)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn matches(platform: &str, triple: &str) -> bool {
+        let target: Target = triple.parse().unwrap();
+        let platform: Platform = platform.parse().unwrap();
+        platform.matches(&target, &target_cfgs(&target))
+    }
+
+    #[test]
+    fn parse_ok() {
+        assert_eq!(
+            "cfg(all(unix, not(target_os = \"macos\")))"
+                .parse::<Platform>()
+                .unwrap(),
+            Platform::Cfg(Cfg::All(vec![
+                Cfg::Name("unix".into()),
+                Cfg::Not(Box::new(Cfg::KeyPair("target_os".into(), "macos".into()))),
+            ]))
+        );
+        assert_eq!(
+            "x86_64-pc-windows-msvc".parse::<Platform>().unwrap(),
+            Platform::Triple("x86_64-pc-windows-msvc".into())
+        );
+    }
+
+    #[test]
+    fn parse_err() {
+        assert!("cfg(all(unix)".parse::<Platform>().is_err());
+        assert!("cfg(target_os = macos)".parse::<Platform>().is_err());
+        assert!("cfg(not(unix, windows))".parse::<Platform>().is_err());
+    }
+
+    #[test]
+    fn matches_ok() {
+        assert!(matches("cfg(windows)", "x86_64-pc-windows-gnu"));
+        assert!(!matches("cfg(windows)", "x86_64-unknown-linux-gnu"));
+        assert!(matches("cfg(unix)", "aarch64-apple-darwin"));
+        assert!(matches(
+            "cfg(all(target_arch = \"wasm32\", not(target_os = \"wasi\")))",
+            "wasm32-unknown-unknown"
+        ));
+        assert!(!matches(
+            "cfg(all(target_arch = \"wasm32\", not(target_os = \"wasi\")))",
+            "wasm32-wasip1"
+        ));
+        assert!(matches(
+            "cfg(any(target_pointer_width = \"32\", target_endian = \"big\"))",
+            "s390x-unknown-linux-gnu"
+        ));
+        assert!(matches(
+            "cfg(target_vendor = \"apple\")",
+            "aarch64-apple-ios"
+        ));
+        assert!(matches(
+            "x86_64-unknown-linux-gnu",
+            "x86_64-unknown-linux-gnu"
+        ));
+    }
+}
@@ -0,0 +1,279 @@
+use std::error::Error;
+
+/// A release section of a changelog
+#[derive(Debug, PartialEq)]
+pub struct Section {
+    /// The version as written in the heading, `Unreleased` for upcoming changes
+    pub version: String,
+    pub body: String,
+}
+
+impl Section {
+    pub fn is_unreleased(&self) -> bool {
+        self.version.eq_ignore_ascii_case("unreleased")
+    }
+
+    /// Whether the section has entries, empty `### Added` style headings do not count
+    pub fn has_entries(&self) -> bool {
+        self.body
+            .lines()
+            .any(|line| !line.trim().is_empty() && heading(line).is_none())
+    }
+
+    fn matches(&self, version: &str) -> bool {
+        let version = version.strip_prefix('v').unwrap_or(version);
+        match (
+            semver::Version::parse(&self.version),
+            semver::Version::parse(version),
+        ) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => self.version == version,
+        }
+    }
+}
+
+/// The release sections of a changelog in Keep a Changelog style. Release headings are the
+/// headings of the level of the first one that names a version or `Unreleased`, e.g.
+/// `## [1.0.0] - 2024-01-01`, `## v1.0.0 (2024-01-01)`, `# Version 1.0.0` or
+/// `## [1.0.0](https://...)`.
+pub fn parse(content: &str) -> Vec<Section> {
+    let lines = outside_code_blocks(content);
+    let Some(level) = lines
+        .iter()
+        .filter_map(|(line, outside)| heading(line).filter(|_| *outside))
+        .find_map(|(level, text)| release_version(text).map(|_| level))
+    else {
+        return Vec::new();
+    };
+
+    let mut sections = Vec::new();
+    let mut current: Option<(String, Vec<&str>)> = None;
+    for (line, outside) in lines {
+        match heading(line).filter(|_| outside) {
+            Some((l, text)) if l <= level => {
+                sections.extend(current.take().map(section));
+                current = release_version(text).map(|version| (version, Vec::new()));
+            }
+            _ => {
+                if let Some((_, body)) = &mut current {
+                    body.push(line);
+                }
+            }
+        }
+    }
+    sections.extend(current.map(section));
+    sections
+}
+
+/// The section for `version`, compared with semver equality when both are valid semver
+pub fn find<'a>(sections: &'a [Section], version: &str) -> Option<&'a Section> {
+    sections.iter().find(|section| section.matches(version))
+}
+
+/// Fails when there are no release notes for `version` or `[Unreleased]` still has entries
+pub fn check(sections: &[Section], version: &str, file: &str) -> Result<(), Box<dyn Error>> {
+    if let Some(unreleased) = sections.iter().find(|section| section.is_unreleased()) {
+        if unreleased.has_entries() {
+            return Err(format!("The `[Unreleased]` section of {file} is not empty").into());
+        }
+    }
+    match find(sections, version) {
+        Some(_) => Ok(()),
+        None => Err(no_section(version, file)),
+    }
+}
+
+pub fn no_section(version: &str, file: &str) -> Box<dyn Error> {
+    format!("No section for version {version} in {file}").into()
+}
+
+/// Trailing link reference definitions like `[1.0.0]: https://...` belong to the whole file
+fn section((version, mut body): (String, Vec<&str>)) -> Section {
+    while let Some(last) = body.last() {
+        let last = last.trim();
+        if last.is_empty() || is_link_definition(last) {
+            body.pop();
+        } else {
+            break;
+        }
+    }
+    let start = body
+        .iter()
+        .position(|line| !line.trim().is_empty())
+        .unwrap_or(body.len());
+
+    Section {
+        version,
+        body: body[start..].join("\n"),
+    }
+}
+
+fn is_link_definition(line: &str) -> bool {
+    line.starts_with('[')
+        && line
+            .split_once("]:")
+            .is_some_and(|(label, _)| !label.contains(']'))
+}
+
+/// Each line and whether it is outside of a fenced code block, where `#` is no heading
+fn outside_code_blocks(content: &str) -> Vec<(&str, bool)> {
+    let mut fence: Option<String> = None;
+    content
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            let marker: String = match trimmed.chars().next() {
+                Some(c @ ('`' | '~')) => trimmed.chars().take_while(|m| *m == c).collect(),
+                _ => String::new(),
+            };
+            match &fence {
+                Some(open) if marker.starts_with(open.as_str()) && trimmed.trim_end() == marker => {
+                    fence = None;
+                    (line, false)
+                }
+                Some(_) => (line, false),
+                None if marker.len() >= 3 => {
+                    fence = Some(marker);
+                    (line, false)
+                }
+                None => (line, true),
+            }
+        })
+        .collect()
+}
+
+/// Level and text of an ATX heading
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.len() - line.trim_start_matches('#').len();
+    let text = &line[level..];
+    if !(1..=6).contains(&level) || !(text.is_empty() || text.starts_with([' ', '\t'])) {
+        return None;
+    }
+    Some((level, text.trim().trim_end_matches('#').trim_end()))
+}
+
+/// The version a release heading names, without brackets, links, a `v` prefix or the date
+fn release_version(text: &str) -> Option<String> {
+    let mut words = text.split_whitespace().peekable();
+    if words
+        .peek()
+        .is_some_and(|word| ["version", "release"].contains(&word.to_lowercase().as_str()))
+    {
+        words.next();
+    }
+
+    let word = words.next()?;
+    let word = word.strip_prefix('[').unwrap_or(word);
+    let word = word.split(']').next().unwrap_or(word);
+    let word = word.trim_end_matches([':', ',']);
+    if word.eq_ignore_ascii_case("unreleased") {
+        return Some("Unreleased".to_owned());
+    }
+
+    let version = word.strip_prefix(['v', 'V']).unwrap_or(word);
+    match version.starts_with(|c: char| c.is_ascii_digit()) && version.contains('.') {
+        true => Some(version.to_owned()),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn release_version_ok() {
+        assert_eq!(
+            release_version("[1.0.0] - 2024-01-01").as_deref(),
+            Some("1.0.0")
+        );
+        assert_eq!(
+            release_version("v1.0.0 (2024-01-01)").as_deref(),
+            Some("1.0.0")
+        );
+        assert_eq!(
+            release_version("[1.0.0-rc.1](https://example.com/compare)").as_deref(),
+            Some("1.0.0-rc.1")
+        );
+        assert_eq!(release_version("Version 0.2.0").as_deref(), Some("0.2.0"));
+        assert_eq!(release_version("0.2.0: Bugfixes").as_deref(), Some("0.2.0"));
+        assert_eq!(
+            release_version("[Unreleased]").as_deref(),
+            Some("Unreleased")
+        );
+        assert_eq!(release_version("Changelog"), None);
+        assert_eq!(release_version("Added"), None);
+    }
+
+    #[test]
+    fn parse_ok() {
+        let content = "# Changelog
+
+All notable changes.
+
+## [Unreleased]
+
+### Added
+
+## [1.1.0] - 2024-02-01
+
+### Fixed
+
+- Crash on empty input
+
+## [1.0.0] - 2024-01-01
+
+- Initial release
+
+[Unreleased]: https://example.com/compare/v1.1.0...HEAD
+[1.1.0]: https://example.com/compare/v1.0.0...v1.1.0
+";
+        let sections = parse(content);
+        assert_eq!(
+            sections,
+            vec![
+                Section {
+                    version: "Unreleased".to_owned(),
+                    body: "### Added".to_owned()
+                },
+                Section {
+                    version: "1.1.0".to_owned(),
+                    body: "### Fixed\n\n- Crash on empty input".to_owned()
+                },
+                Section {
+                    version: "1.0.0".to_owned(),
+                    body: "- Initial release".to_owned()
+                },
+            ]
+        );
+        assert!(!sections[0].has_entries());
+        assert_eq!(find(&sections, "v1.1.0"), Some(&sections[1]));
+        assert!(check(&sections, "1.1.0", "CHANGELOG.md").is_ok());
+        assert!(check(&sections, "1.2.0", "CHANGELOG.md").is_err());
+    }
+
+    #[test]
+    fn parse_code_block() {
+        let content = "## 1.1.0
+
+- Install with:
+
+  ```bash
+  # install the binary
+  cargo install foo
+  ```
+
+~~~
+## 1.0.0
+~~~
+
+## 1.0.0
+";
+        let sections = parse(content);
+        assert_eq!(sections.len(), 2);
+        assert_eq!(
+            sections[0].body,
+            "- Install with:\n\n  ```bash\n  # install the binary\n  cargo install foo\n  ```\n\n~~~\n## 1.0.0\n~~~"
+        );
+    }
+}
@@ -1,8 +1,27 @@
-use std::{error::Error, path::PathBuf};
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
 
 use clap::{Args, Parser, Subcommand};
 
-use crate::{delimiter::Delimiter, output_format::OutputFormat, terminator::Terminator};
+use crate::{
+    authors::Author,
+    cfg::CfgValue,
+    delimiter::Delimiter,
+    error::NotSpecified,
+    graph::{AffectedFormat, Cycle, Graph, GraphFormat},
+    json::Json,
+    oci::OciFormat,
+    output_format::OutputFormat,
+    packaging::PackagingFormat,
+    sbom::SbomFormat,
+    source::Source,
+    spdx,
+    targets::Target,
+    terminator::Terminator,
+    workspace,
+};
 
 #[derive(Parser, Debug)]
 pub struct All {
@@ -10,11 +29,320 @@ pub struct All {
     pub output_format: OutputFormat,
 }
 
+#[derive(Parser, Debug)]
+pub struct Artifacts {
+    #[clap(
+        long,
+        value_name = "TRIPLE",
+        help = "Target triple to name the artifacts for"
+    )]
+    pub target: Target,
+
+    #[clap(long, help = "Print paths under `<target-dir>/<triple>/<profile>/`")]
+    pub paths: bool,
+
+    #[clap(long, default_value = "dev", requires = "paths")]
+    pub profile: String,
+
+    #[clap(
+        long,
+        value_name = "DIR",
+        requires = "paths",
+        help = "(Defaults to the target directory Cargo uses for the workspace)"
+    )]
+    pub target_dir: Option<PathBuf>,
+}
+
+#[derive(Parser, Debug)]
+pub struct Config {
+    #[clap(help = "Dotted config key, e.g. `build.target` or `alias`")]
+    pub key: String,
+
+    #[clap(
+        long,
+        help = "Print the file or environment variable each value came from"
+    )]
+    pub show_origin: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct Dependencies {
+    #[clap(
+        long,
+        value_name = "TRIPLE",
+        help = "Only dependencies that apply to this target triple"
+    )]
+    pub target: Option<Target>,
+
+    #[clap(
+        long,
+        value_name = "KEY[=VALUE]",
+        requires = "target",
+        help = "Extra cfg to set when evaluating `cfg(...)`, may be repeated"
+    )]
+    pub cfg: Vec<CfgValue>,
+}
+
+#[derive(Parser, Debug)]
+pub struct Affected {
+    #[clap(
+        long,
+        value_name = "PATH",
+        help = "Read the changed paths from this file instead of stdin"
+    )]
+    pub file: Option<PathBuf>,
+
+    #[clap(
+        long,
+        value_name = "DIR",
+        help = "Directory the changed paths are relative to. (Defaults to the git repository root)"
+    )]
+    pub base_dir: Option<PathBuf>,
+
+    #[clap(
+        long,
+        default_value = "names",
+        help = "Print member names, paths or a matrix"
+    )]
+    pub format: AffectedFormat,
+}
+
+impl Affected {
+    pub fn match_affected(
+        &self,
+        graph: &Graph,
+        base: &Path,
+        changed: &[PathBuf],
+        delimiter: &Delimiter,
+    ) -> String {
+        let members = graph.affected(base, changed).into_iter().map(|name| {
+            let dir = &graph.members[name].dir;
+            let path = match dir.strip_prefix(&graph.root_dir) {
+                Ok(path) if path.as_os_str().is_empty() => ".".to_owned(),
+                Ok(path) => path.to_string_lossy().to_string(),
+                Err(_) => dir.to_string_lossy().to_string(),
+            };
+            (name, path)
+        });
+
+        match self.format {
+            AffectedFormat::Names => members
+                .map(|(name, _)| name.to_owned())
+                .collect::<Vec<_>>()
+                .join(&delimiter.to_string()),
+            AffectedFormat::Paths => members
+                .map(|(_, path)| path)
+                .collect::<Vec<_>>()
+                .join(&delimiter.to_string()),
+            AffectedFormat::Matrix => {
+                let include = members
+                    .map(|(name, path)| {
+                        Json::object(vec![("name", name.into()), ("path", path.into())])
+                    })
+                    .collect();
+                Json::object(vec![("include", include)]).to_string()
+            }
+        }
+    }
+}
+
+#[derive(Parser, Debug)]
+pub struct Lints {
+    #[clap(
+        long,
+        help = "Print `-D`/`-W`/`-A` flags for rustc and clippy in priority order"
+    )]
+    pub as_flags: bool,
+
+    #[clap(
+        long,
+        help = "Only lints of this tool, e.g. `rust`, `clippy` or `rustdoc`"
+    )]
+    pub tool: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct LockSummary {
+    #[clap(long, help = "Print as JSON")]
+    pub json: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct Sbom {
+    #[clap(long, help = "Document format")]
+    pub format: SbomFormat,
+}
+
+#[derive(Parser, Debug)]
+pub struct Licenses {
+    #[clap(
+        long,
+        value_name = "IDS",
+        value_delimiter = ',',
+        help = "Fail unless every crate can be used under these SPDX license ids"
+    )]
+    pub allow: Vec<String>,
+
+    #[clap(
+        long,
+        value_name = "IDS",
+        value_delimiter = ',',
+        help = "Fail if a crate can only be used under one of these SPDX license ids"
+    )]
+    pub deny: Vec<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct Debian {
+    #[command(subcommand)]
+    pub file: DebianFile,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DebianFile {
+    #[clap(about = "Binary package stanza of `debian/control`")]
+    Control,
+    #[clap(about = "Machine-readable `debian/copyright` file")]
+    Copyright,
+}
+
+#[derive(Parser, Debug)]
+pub struct Packaging {
+    #[clap(long, help = "Package format")]
+    pub format: PackagingFormat,
+
+    #[clap(flatten)]
+    pub tag: Tag,
+}
+
+#[derive(Parser, Debug)]
+pub struct OciLabels {
+    #[clap(
+        long,
+        default_value = "args",
+        help = "Print as arguments, a Dockerfile instruction or JSON"
+    )]
+    pub format: OciFormat,
+
+    #[clap(long, help = "Commit the image is built from, e.g. `$GITHUB_SHA`")]
+    pub revision: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct Changelog {
+    #[clap(
+        long,
+        value_name = "PATH",
+        help = "Changelog to read. (Defaults to `CHANGELOG.md` next to the manifest)"
+    )]
+    pub file: Option<PathBuf>,
+
+    #[clap(long, help = "Version to print. (Defaults to `package.version`)")]
+    pub version: Option<String>,
+
+    #[clap(
+        long,
+        help = "Fail when the version has no section or `[Unreleased]` is not empty"
+    )]
+    pub check: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct Check {
+    #[command(subcommand)]
+    pub check: CheckCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CheckCommand {
+    #[clap(about = "Verify offline that Cargo.lock satisfies every manifest requirement")]
+    Lock,
+    #[clap(
+        about = "Compare the version of package.json or pyproject.toml files with package.version"
+    )]
+    VersionSync {
+        #[clap(required = true, help = "package.json or pyproject.toml files")]
+        files: Vec<PathBuf>,
+
+        #[clap(long, help = "Rewrite the versions that differ")]
+        fix: bool,
+    },
+    #[clap(
+        name = "tag",
+        about = "Verify that a release tag matches the package version"
+    )]
+    ReleaseTag {
+        #[clap(help = "Tag name or `refs/tags/` ref. (Defaults to `$GITHUB_REF`)")]
+        tag: Option<String>,
+
+        #[clap(flatten)]
+        pattern: Tag,
+    },
+}
+
+#[derive(Parser, Debug)]
+pub struct Diff {
+    #[clap(help = "Directory, manifest path or git revision of the old manifest")]
+    pub a: String,
+
+    #[clap(help = "Directory, manifest path or git revision of the new manifest")]
+    pub b: String,
+
+    #[clap(
+        long,
+        value_name = "RULE",
+        value_delimiter = ',',
+        help = "Fail on changes: `any`, `version`, `major`, `minor`, `patch` or a field name"
+    )]
+    pub fail_on: Vec<String>,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum MaybeCommand {
     #[clap(flatten)]
     Command(Command),
+    #[clap(flatten)]
+    Derived(Derived),
     All(All),
+    #[clap(about = "File names Cargo produces for the lib and bin targets")]
+    Artifacts(Artifacts),
+    #[clap(about = "Values from `.cargo/config.toml`, merged the way Cargo does")]
+    Config(Config),
+    #[clap(about = "Dependencies of the package, optionally for one target triple")]
+    Dependencies(Dependencies),
+    #[clap(
+        name = "workspace.graph",
+        about = "Path dependency graph between the workspace members"
+    )]
+    WorkspaceGraph(GraphOutput),
+    #[clap(
+        name = "workspace.affected",
+        about = "Members affected by the changed file paths read from stdin"
+    )]
+    WorkspaceAffected(Affected),
+    #[clap(about = "Effective `[lints]` of the package after workspace inheritance")]
+    Lints(Lints),
+    #[clap(about = "Fields that changed between two manifests or git revisions")]
+    Diff(Diff),
+    #[clap(
+        name = "lock.summary",
+        about = "Lockfile format, package sources and crates locked in several versions"
+    )]
+    LockSummary(LockSummary),
+    #[clap(about = "Consistency checks that fail with an explanation")]
+    Check(Check),
+    #[clap(about = "Software bill of materials from the manifest and Cargo.lock")]
+    Sbom(Sbom),
+    #[clap(about = "Licenses of the locked crates, read from the local registry cache")]
+    Licenses(Licenses),
+    #[clap(about = "Debian packaging files, with overrides from `[package.metadata.deb]`")]
+    Debian(Debian),
+    #[clap(about = "Distribution package skeletons for the bin targets")]
+    Packaging(Packaging),
+    #[clap(about = "`org.opencontainers.image.*` labels for container images")]
+    OciLabels(OciLabels),
+    #[clap(about = "Release notes of a version from the changelog")]
+    Changelog(Changelog),
 }
 
 #[derive(Parser)]
@@ -46,6 +374,14 @@ pub struct Cli {
         help = "String terminator for the output that is returned"
     )]
     pub terminator: Option<Terminator>,
+
+    #[clap(
+        global = true,
+        long,
+        value_name = "GIT_REF",
+        help = "Read the manifests from this git revision instead of the working tree"
+    )]
+    pub rev: Option<String>,
 }
 
 #[derive(Args, Clone, Debug, Default)]
@@ -118,6 +454,275 @@ impl Version {
     }
 }
 
+#[derive(Args, Clone, Debug, Default)]
+#[group(required = false, multiple = false)]
+pub struct License {
+    #[arg(long, help = "List the individual license identifiers")]
+    ids: bool,
+
+    #[arg(long, help = "Print the canonical SPDX expression")]
+    normalize: bool,
+
+    #[arg(
+        long,
+        help = "Fail on unknown license identifiers or deprecated syntax"
+    )]
+    check: bool,
+}
+
+impl License {
+    pub fn match_license(
+        &self,
+        expression: &str,
+        delimiter: &Delimiter,
+    ) -> Result<String, Box<dyn Error>> {
+        if !(self.ids || self.normalize || self.check) {
+            return Ok(expression.to_owned());
+        }
+
+        let parsed = spdx::parse(expression)?;
+
+        if self.check {
+            if !parsed.issues.is_empty() {
+                return Err(spdx::CheckFailed(parsed.issues).into());
+            }
+            return Ok(expression.to_owned());
+        }
+
+        if self.ids {
+            return Ok(parsed.expression.ids().join(&delimiter.to_string()));
+        }
+
+        Ok(parsed.expression.normalize().to_string())
+    }
+}
+
+#[derive(Args, Clone, Debug, Default)]
+pub struct SharedDependencies {
+    #[arg(
+        long,
+        conflicts_with = "unused",
+        help = "List the members inheriting each dependency with `workspace = true`"
+    )]
+    usage: bool,
+
+    #[arg(long, help = "Print only dependencies no member inherits")]
+    unused: bool,
+
+    #[arg(long, help = "Print as JSON")]
+    json: bool,
+}
+
+impl SharedDependencies {
+    pub fn match_dependencies(
+        &self,
+        source: &Source,
+        root_manifest: &Path,
+        root: &cargo_toml::Manifest,
+        delimiter: &Delimiter,
+    ) -> Result<String, Box<dyn Error>> {
+        let delim_string = delimiter.to_string();
+        let dependencies = root
+            .workspace
+            .as_ref()
+            .map(|ws| &ws.dependencies)
+            .ok_or(NotSpecified("workspace"))?;
+
+        if self.usage {
+            let usage = workspace::dependency_usage(source, root_manifest, root)?;
+            return Ok(match self.json {
+                true => Json::object(
+                    usage
+                        .into_iter()
+                        .map(|(name, users)| (name, users.into_iter().collect())),
+                )
+                .to_string(),
+                false => usage
+                    .iter()
+                    .map(|(name, users)| match users.is_empty() {
+                        true => format!("{name}:"),
+                        false => format!("{name}: {}", users.join(", ")),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(&delim_string),
+            });
+        }
+
+        if self.unused {
+            let unused = workspace::dependency_usage(source, root_manifest, root)?
+                .into_iter()
+                .filter(|(_, users)| users.is_empty())
+                .map(|(name, _)| name);
+            return Ok(match self.json {
+                true => unused.collect::<Json>().to_string(),
+                false => unused.collect::<Vec<_>>().join(&delim_string),
+            });
+        }
+
+        Ok(match self.json {
+            true => dependencies
+                .iter()
+                .map(|(name, dep)| workspace::dependency_json(name, dep))
+                .collect::<Json>()
+                .to_string(),
+            false => dependencies
+                .iter()
+                .map(|(name, dep)| workspace::describe_dependency(name, dep))
+                .collect::<Vec<_>>()
+                .join(&delim_string),
+        })
+    }
+}
+
+#[derive(Args, Clone, Debug, Default)]
+#[group(required = false, multiple = false)]
+pub struct GraphOutput {
+    #[arg(
+        long,
+        help = "Print the members in the order they can be published, skipping `publish = false`"
+    )]
+    publish_order: bool,
+
+    #[arg(long, help = "Render the graph for Graphviz or Mermaid")]
+    format: Option<GraphFormat>,
+
+    #[arg(
+        long,
+        help = "Print dependency cycles, cycles through dev-dependencies are marked `(dev)`"
+    )]
+    cycles: bool,
+}
+
+impl GraphOutput {
+    pub fn match_graph(&self, graph: &Graph, delimiter: &Delimiter) -> Result<String, Cycle> {
+        let delim_string = delimiter.to_string();
+
+        if self.publish_order {
+            return Ok(graph.publish_order()?.join(&delim_string));
+        }
+
+        if let Some(format) = &self.format {
+            return Ok(graph.render(format));
+        }
+
+        if self.cycles {
+            let normal = graph.cycles(false).into_iter().map(|c| c.join(" -> "));
+            let dev = graph
+                .cycles(true)
+                .into_iter()
+                .map(|c| format!("{} (dev)", c.join(" -> ")));
+            return Ok(normal.chain(dev).collect::<Vec<_>>().join(&delim_string));
+        }
+
+        Ok(graph
+            .edges
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(&delim_string))
+    }
+}
+
+#[derive(Args, Clone, Debug, Default)]
+#[group(required = false, multiple = false)]
+pub struct Authors {
+    #[arg(long, help = "Print only the author names")]
+    names: bool,
+
+    #[arg(long, help = "Print only the author emails")]
+    emails: bool,
+
+    #[arg(
+        long,
+        help = "Print the authors as JSON objects with `name` and `email`"
+    )]
+    json: bool,
+}
+
+impl Authors {
+    pub fn match_authors(&self, authors: &[String], delimiter: &Delimiter) -> String {
+        let delim_string = delimiter.to_string();
+        let parsed = authors.iter().map(|a| Author::parse(a));
+
+        if self.names {
+            return parsed
+                .filter_map(|a| a.name)
+                .collect::<Vec<_>>()
+                .join(&delim_string);
+        }
+
+        if self.emails {
+            return parsed
+                .filter_map(|a| a.email)
+                .collect::<Vec<_>>()
+                .join(&delim_string);
+        }
+
+        if self.json {
+            return Json::Array(parsed.map(|a| a.to_json()).collect()).to_string();
+        }
+
+        authors.join(&delim_string)
+    }
+}
+
+#[derive(Args, Clone, Debug, Default)]
+pub struct Tag {
+    #[arg(
+        long,
+        value_name = "PATTERN",
+        help = "Tag name with `{name}` and `{version}` placeholders. (Defaults to `v{version}`)"
+    )]
+    pub tag_pattern: Option<String>,
+}
+
+impl Tag {
+    pub fn pattern(&self) -> &str {
+        self.tag_pattern.as_deref().unwrap_or("v{version}")
+    }
+
+    pub fn render(&self, name: &str, version: &str) -> String {
+        crate::urls::render_tag(self.pattern(), name, version)
+    }
+}
+
+/// Values computed from the manifest rather than read from it, left out of `all`
+#[derive(Subcommand, Debug)]
+pub enum Derived {
+    #[clap(name = "package.documentation")]
+    PackageDocumentation,
+
+    #[clap(name = "urls.forge")]
+    UrlsForge,
+
+    #[clap(name = "urls.slug")]
+    UrlsSlug,
+
+    #[clap(name = "urls.web")]
+    UrlsWeb,
+
+    #[clap(name = "urls.tag")]
+    UrlsTag {
+        #[clap(flatten)]
+        tag: Tag,
+    },
+
+    #[clap(name = "urls.raw")]
+    UrlsRaw {
+        #[arg(help = "File path relative to the repository root. (Defaults to `Cargo.toml`)")]
+        file: Option<String>,
+
+        #[clap(flatten)]
+        tag: Tag,
+    },
+
+    #[clap(name = "urls.docs_rs")]
+    UrlsDocsRs,
+
+    #[clap(name = "urls.crates_io")]
+    UrlsCratesIo,
+}
+
 #[derive(
     Subcommand, strum::Display, Debug, strum::EnumIter, strum::EnumString, strum::VariantNames,
 )]
@@ -146,7 +751,10 @@ pub enum Command {
     PackageWorkspace,
 
     #[clap(name = "package.authors")]
-    PackageAuthors,
+    PackageAuthors {
+        #[clap(flatten)]
+        inner: Authors,
+    },
 
     #[clap(name = "package.links")]
     PackageLinks,
@@ -173,7 +781,10 @@ pub enum Command {
     PackageInclude,
 
     #[clap(name = "package.license")]
-    PackageLicense,
+    PackageLicense {
+        #[clap(flatten)]
+        inner: License,
+    },
 
     #[clap(name = "package.license_file")]
     PackageLicenseFile,
@@ -200,8 +811,29 @@ pub enum Command {
     #[clap(name = "workspace.default_members")]
     WorkspaceDefaultMembers,
 
+    #[clap(name = "workspace.root")]
+    WorkspaceRoot,
+
+    #[clap(name = "workspace.dependencies")]
+    WorkspaceDependencies {
+        #[clap(flatten)]
+        inner: SharedDependencies,
+    },
+
+    #[clap(name = "workspace.lints")]
+    WorkspaceLints,
+
+    #[clap(name = "workspace.metadata")]
+    WorkspaceMetadata {
+        #[clap(help = "Dotted key inside `[workspace.metadata]`")]
+        key: Option<String>,
+    },
+
     #[clap(name = "workspace.package.authors")]
-    WorkspacePackageAuthors,
+    WorkspacePackageAuthors {
+        #[clap(flatten)]
+        inner: Authors,
+    },
 
     #[clap(name = "workspace.package.categories")]
     WorkspacePackageCategories,
@@ -228,7 +860,10 @@ pub enum Command {
     WorkspacePackageKeywords,
 
     #[clap(name = "workspace.package.license")]
-    WorkspacePackageLicense,
+    WorkspacePackageLicense {
+        #[clap(flatten)]
+        inner: License,
+    },
 
     #[clap(name = "workspace.package.license_file")]
     WorkspacePackageLicenseFile,
@@ -252,3 +887,16 @@ pub enum Command {
         inner: Version,
     },
 }
+
+impl Command {
+    /// The dotted name used on the command line, e.g. `workspace.package.rust_version`
+    pub fn field_name(&self) -> String {
+        let name = self.to_string();
+        for prefix in ["workspace_package_", "workspace_", "package_", "urls_"] {
+            if let Some(rest) = name.strip_prefix(prefix) {
+                return format!("{}{rest}", prefix.replace('_', "."));
+            }
+        }
+        name
+    }
+}
@@ -0,0 +1,277 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use toml::Value;
+
+/// Where a config value was defined
+#[derive(Debug, PartialEq, Clone)]
+pub enum Origin {
+    File(PathBuf),
+    Env(String),
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "file:{}", path.display()),
+            Self::Env(var) => write!(f, "env:{var}"),
+        }
+    }
+}
+
+/// A merged config value. Leaves and array items remember the file they came from.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Node {
+    Leaf(Value, Origin),
+    Array(Vec<(Value, Origin)>),
+    Table(BTreeMap<String, Node>),
+}
+
+#[derive(Debug)]
+pub struct KeyNotFound(pub String);
+
+impl fmt::Display for KeyNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` not specified in cargo config", self.0)
+    }
+}
+
+impl Error for KeyNotFound {}
+
+impl Node {
+    fn from_value(value: Value, origin: &Origin) -> Self {
+        match value {
+            Value::Table(table) => Self::Table(
+                table
+                    .into_iter()
+                    .map(|(k, v)| (k, Self::from_value(v, origin)))
+                    .collect(),
+            ),
+            Value::Array(items) => {
+                Self::Array(items.into_iter().map(|v| (v, origin.clone())).collect())
+            }
+            value => Self::Leaf(value, origin.clone()),
+        }
+    }
+
+    /// Merge a config with higher precedence into this one, the way Cargo does:
+    /// tables are merged recursively, arrays are joined and other values are replaced.
+    fn merge(&mut self, other: Node) {
+        match (self, other) {
+            (Self::Table(this), Self::Table(other)) => {
+                for (key, value) in other {
+                    match this.get_mut(&key) {
+                        Some(existing) => existing.merge(value),
+                        None => {
+                            this.insert(key, value);
+                        }
+                    }
+                }
+            }
+            (Self::Array(this), Self::Array(other)) => this.extend(other),
+            (this, other) => *this = other,
+        }
+    }
+
+    /// One line per value. Strings are printed raw unless they are part of a table, which
+    /// is printed as `key = value` pairs relative to the queried key. Arrays in a table are
+    /// printed on one line unless the origin of each item is requested.
+    pub fn render(&self, show_origin: bool) -> Vec<String> {
+        self.render_at("", show_origin)
+    }
+
+    fn render_at(&self, key: &str, show_origin: bool) -> Vec<String> {
+        let line = |value: &Value, origin: &Origin| {
+            let value = match (key, value) {
+                ("", Value::String(s)) => s.clone(),
+                ("", value) => value.to_string(),
+                (key, value) => format!("{key} = {value}"),
+            };
+            match show_origin {
+                true => format!("{origin}\t{value}"),
+                false => value,
+            }
+        };
+
+        match self {
+            Self::Leaf(value, origin) => vec![line(value, origin)],
+            Self::Array(items) if key.is_empty() || show_origin => items
+                .iter()
+                .map(|(value, origin)| line(value, origin))
+                .collect(),
+            Self::Array(items) => {
+                let values = items.iter().map(|(value, _)| value.clone()).collect();
+                vec![format!("{key} = {}", Value::Array(values))]
+            }
+            Self::Table(table) => table
+                .iter()
+                .flat_map(|(child, node)| match key {
+                    "" => node.render_at(child, show_origin),
+                    key => node.render_at(&format!("{key}.{child}"), show_origin),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// All config files that apply to `dir`, from lowest to highest precedence
+pub fn config_files(dir: &Path, cargo_home: Option<&Path>) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = dir
+        .ancestors()
+        .filter_map(|ancestor| find_config(&ancestor.join(".cargo")))
+        .collect();
+
+    if let Some(home_config) = cargo_home.and_then(find_config) {
+        if !files.contains(&home_config) {
+            files.push(home_config);
+        }
+    }
+
+    files.reverse();
+    files
+}
+
+/// Cargo prefers the legacy `config` file when both exist
+fn find_config(dir: &Path) -> Option<PathBuf> {
+    ["config", "config.toml"]
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+pub fn cargo_home() -> Option<PathBuf> {
+    std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cargo")))
+}
+
+/// Read and merge all config files that apply to `dir`
+pub fn load(dir: &Path) -> Result<Node, Box<dyn Error>> {
+    let mut root = Node::Table(BTreeMap::new());
+
+    for file in config_files(dir, cargo_home().as_deref()) {
+        let content = std::fs::read_to_string(&file)?;
+        let table: toml::Table = toml::from_str(&content)
+            .map_err(|err| format!("Failed to parse {}: {err}", file.display()))?;
+        root.merge(Node::from_value(Value::Table(table), &Origin::File(file)));
+    }
+
+    Ok(root)
+}
+
+/// Look up a dotted key and apply `CARGO_*` environment overrides
+pub fn get(root: &Node, key: &str) -> Result<Node, KeyNotFound> {
+    let mut node = Some(root);
+    for part in key.split('.') {
+        node = match node {
+            Some(Node::Table(table)) => table.get(part),
+            _ => None,
+        };
+    }
+
+    match node.cloned() {
+        Some(mut node) => {
+            apply_env(&mut node, key);
+            Ok(node)
+        }
+        None => {
+            let var = env_var_name(key);
+            match std::env::var(&var) {
+                Ok(value) => Ok(Node::Leaf(Value::String(value), Origin::Env(var))),
+                Err(_) => Err(KeyNotFound(key.to_owned())),
+            }
+        }
+    }
+}
+
+fn env_var_name(key: &str) -> String {
+    format!("CARGO_{}", key.to_uppercase().replace(['.', '-'], "_"))
+}
+
+fn apply_env(node: &mut Node, key: &str) {
+    let var = env_var_name(key);
+    let value = std::env::var(&var);
+    let origin = Origin::Env(var);
+
+    match (node, value) {
+        (Node::Table(table), _) => {
+            for (child_key, child) in table.iter_mut() {
+                apply_env(child, &format!("{key}.{child_key}"));
+            }
+        }
+        (_, Err(_)) => {}
+        (Node::Array(items), Ok(value)) => items.extend(
+            value
+                .split_whitespace()
+                .map(|item| (Value::String(item.to_owned()), origin.clone())),
+        ),
+        (Node::Leaf(existing, existing_origin), Ok(value)) => {
+            *existing = match existing {
+                Value::Boolean(_) => value.parse().map(Value::Boolean).ok(),
+                Value::Integer(_) => value.parse().map(Value::Integer).ok(),
+                _ => None,
+            }
+            .unwrap_or(Value::String(value));
+            *existing_origin = origin;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn node(toml: &str, file: &str) -> Node {
+        let table: toml::Table = toml::from_str(toml).unwrap();
+        Node::from_value(Value::Table(table), &Origin::File(file.into()))
+    }
+
+    #[test]
+    fn merge_ok() {
+        let mut root = node(
+            r#"
+            build.target = "x86_64-unknown-linux-gnu"
+            build.rustflags = ["-Dwarnings"]
+            alias.b = "build"
+            "#,
+            "parent",
+        );
+        root.merge(node(
+            r#"
+            build.target = "wasm32-unknown-unknown"
+            build.rustflags = ["-Cdebuginfo=0"]
+            alias.t = "test"
+            "#,
+            "child",
+        ));
+
+        assert_eq!(
+            root.render(true),
+            vec![
+                "file:parent\talias.b = \"build\"",
+                "file:child\talias.t = \"test\"",
+                "file:parent\tbuild.rustflags = \"-Dwarnings\"",
+                "file:child\tbuild.rustflags = \"-Cdebuginfo=0\"",
+                "file:child\tbuild.target = \"wasm32-unknown-unknown\"",
+            ]
+        );
+
+        assert_eq!(
+            root.render(false)[2],
+            "build.rustflags = [\"-Dwarnings\", \"-Cdebuginfo=0\"]"
+        );
+    }
+
+    #[test]
+    fn env_var_name_ok() {
+        assert_eq!(env_var_name("build.target-dir"), "CARGO_BUILD_TARGET_DIR");
+        assert_eq!(
+            env_var_name("registries.my-registry.index"),
+            "CARGO_REGISTRIES_MY_REGISTRY_INDEX"
+        );
+    }
+}
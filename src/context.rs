@@ -0,0 +1,411 @@
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use cargo_toml::{Manifest, Package, PackageTemplate, Workspace};
+
+use crate::{
+    cli,
+    delimiter::Delimiter,
+    error::{InvalidSemver, NotSpecified},
+    graph, lints,
+    source::Source,
+    urls::{self, Repository},
+    workspace,
+};
+
+/// A manifest and where it was read from
+pub struct Context {
+    pub source: Source,
+    pub manifest_path: PathBuf,
+    pub manifest: Manifest,
+}
+
+impl Context {
+    /// Find and read the manifest for `entry`, searching upwards
+    pub fn load(source: Source, entry: &Path) -> Result<Self, Box<dyn Error>> {
+        let manifest_path =
+            search_manifest_path(&source, entry).ok_or_else(|| -> Box<dyn Error> {
+                match &source {
+                    Source::Git(revision) => revision.not_found(&entry.join("Cargo.toml")).into(),
+                    Source::WorkingTree => "No manifest found".into(),
+                }
+            })?;
+        let manifest = source.manifest(&manifest_path)?;
+
+        Ok(Self {
+            source,
+            manifest_path,
+            manifest,
+        })
+    }
+
+    pub fn package(&self) -> Result<Package, NotSpecified> {
+        self.manifest.package.clone().ok_or(NotSpecified("package"))
+    }
+
+    pub fn workspace_root(&self) -> Result<PathBuf, Box<dyn Error>> {
+        let root = workspace::find_root(&self.source, &self.manifest_path, &self.manifest)?;
+        Ok(root.ok_or(NotSpecified("workspace"))?)
+    }
+
+    pub fn workspace(&self) -> Result<Workspace, Box<dyn Error>> {
+        match &self.manifest.workspace {
+            Some(workspace) => Ok(workspace.clone()),
+            None => Ok(self
+                .source
+                .manifest(&self.workspace_root()?)?
+                .workspace
+                .ok_or(NotSpecified("workspace"))?),
+        }
+    }
+
+    /// `Cargo.lock` next to the workspace root, or next to the manifest outside a workspace
+    pub fn lockfile_path(&self) -> Result<PathBuf, Box<dyn Error>> {
+        let root = workspace::find_root(&self.source, &self.manifest_path, &self.manifest)?;
+        Ok(root
+            .unwrap_or_else(|| self.manifest_path.clone())
+            .with_file_name("Cargo.lock"))
+    }
+
+    /// Package names of all workspace members, or of the package outside a workspace
+    pub fn member_names(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        match workspace::find_root(&self.source, &self.manifest_path, &self.manifest)? {
+            Some(root_manifest) => {
+                let root = self.source.manifest(&root_manifest)?;
+                let graph = graph::Graph::load(&self.source, &root_manifest, &root)?;
+                Ok(graph.members.into_keys().collect())
+            }
+            None => Ok(vec![self.package()?.name().to_owned()]),
+        }
+    }
+
+    /// Manifests of all workspace members, or of the package outside a workspace
+    pub fn member_manifests(&self) -> Result<Vec<Manifest>, Box<dyn Error>> {
+        match workspace::find_root(&self.source, &self.manifest_path, &self.manifest)? {
+            Some(root_manifest) => {
+                let root = self.source.manifest(&root_manifest)?;
+                workspace::members(&self.source, &root_manifest, &root)
+                    .iter()
+                    .map(|dir| self.source.manifest(&dir.join("Cargo.toml")))
+                    .collect()
+            }
+            None => Ok(vec![self.manifest.clone()]),
+        }
+    }
+
+    fn ws_package(&self) -> Result<PackageTemplate, Box<dyn Error>> {
+        let package = self.workspace()?.package;
+        Ok(package.ok_or(NotSpecified("workspace.package"))?)
+    }
+
+    fn repository(&self) -> Result<Repository, Box<dyn Error>> {
+        let repository = self
+            .package()?
+            .repository()
+            .ok_or(NotSpecified("package.repository"))?
+            .to_owned();
+        Ok(Repository::parse(&repository)?)
+    }
+
+    /// The value of a field derived from the manifest
+    pub fn derived(&self, cmd: &cli::Derived) -> Result<String, Box<dyn Error>> {
+        let output = match cmd {
+            cli::Derived::PackageDocumentation => {
+                let package = self.package()?;
+                match package.documentation() {
+                    Some(docs) => docs.to_string(),
+                    None => urls::docs_rs(package.name(), package.version()),
+                }
+            }
+            cli::Derived::UrlsForge => self.repository()?.forge.to_string(),
+            cli::Derived::UrlsSlug => self.repository()?.slug,
+            cli::Derived::UrlsWeb => self.repository()?.web_url(),
+            cli::Derived::UrlsTag { tag } => {
+                let package = self.package()?;
+                self.repository()?
+                    .tag_url(&tag.render(package.name(), package.version()))?
+            }
+            cli::Derived::UrlsRaw { file, tag } => {
+                let package = self.package()?;
+                self.repository()?.raw_url(
+                    &tag.render(package.name(), package.version()),
+                    file.as_deref().unwrap_or("Cargo.toml"),
+                )?
+            }
+            cli::Derived::UrlsDocsRs => {
+                let package = self.package()?;
+                urls::docs_rs(package.name(), package.version())
+            }
+            cli::Derived::UrlsCratesIo => {
+                let package = self.package()?;
+                urls::crates_io(package.name(), package.version())
+            }
+        };
+        Ok(output)
+    }
+
+    /// The value of a single field
+    pub fn get(&self, cmd: &cli::Command, delimiter: &Delimiter) -> Result<String, Box<dyn Error>> {
+        let delim_string = delimiter.to_string();
+
+        let output = match cmd {
+            cli::Command::PackageVersion { inner } => {
+                let v: semver::Version =
+                    self.package()?.version().parse().map_err(InvalidSemver)?;
+                inner.match_version(v, delimiter)?
+            }
+            cli::Command::PackageAuthors { inner } => {
+                inner.match_authors(self.package()?.authors(), delimiter)
+            }
+
+            cli::Command::PackageEdition => self.package()?.edition().to_string(),
+            cli::Command::PackageName => self.package()?.name().to_string(),
+            cli::Command::PackageHomepage => self
+                .package()?
+                .homepage()
+                .ok_or(NotSpecified("package.homepage"))?
+                .to_string(),
+            cli::Command::PackageKeywords => self.package()?.keywords().join(&delim_string),
+            cli::Command::PackageLicense { inner } => inner.match_license(
+                self.package()?
+                    .license()
+                    .ok_or(NotSpecified("package.license"))?,
+                delimiter,
+            )?,
+            cli::Command::PackageLinks => self
+                .package()?
+                .links()
+                .ok_or(NotSpecified("package.links"))?
+                .to_string(),
+            cli::Command::PackageDescription => self
+                .package()?
+                .description()
+                .ok_or(NotSpecified("package.description"))?
+                .to_string(),
+            cli::Command::PackageCategories => self.package()?.categories().join(&delim_string),
+            cli::Command::PackageRustVersion => self
+                .package()?
+                .rust_version()
+                .ok_or(NotSpecified("package.rust_version"))?
+                .to_string(),
+            cli::Command::PackageBuild => self
+                .package()?
+                .build
+                .ok_or(NotSpecified("package.build"))?
+                .as_path()
+                .unwrap()
+                .to_string_lossy()
+                .to_string(),
+
+            cli::Command::PackageWorkspace => self
+                .package()?
+                .workspace
+                .ok_or(NotSpecified("package.workspace"))?
+                .to_string_lossy()
+                .to_string(),
+
+            cli::Command::PackageReadme => self
+                .package()?
+                .readme()
+                .as_path()
+                .ok_or(NotSpecified("package.readme"))?
+                .to_string_lossy()
+                .to_string(),
+
+            cli::Command::PackageExclude => self.package()?.exclude().join(&delim_string),
+            cli::Command::PackageInclude => self.package()?.include().join(&delim_string),
+            cli::Command::PackageLicenseFile => self
+                .package()?
+                .license_file()
+                .ok_or(NotSpecified("package.license_file"))?
+                .to_string_lossy()
+                .to_string(),
+
+            cli::Command::PackageRepository => self
+                .package()?
+                .repository()
+                .ok_or(NotSpecified("package.repository"))?
+                .to_string(),
+
+            cli::Command::PackageDefaultRun => self
+                .package()?
+                .default_run
+                .ok_or(NotSpecified("package.default_run"))?
+                .to_string(),
+
+            cli::Command::PackagePublish => match self.package()?.publish() {
+                cargo_toml::Publish::Flag(flag) => flag.to_string(),
+                cargo_toml::Publish::Registry(list) => list.join(&delim_string),
+            },
+            cli::Command::PackageResolver => self
+                .package()?
+                .resolver
+                .ok_or(NotSpecified("package.resolver"))?
+                .to_string(),
+
+            cli::Command::PackageMetadata => self
+                .package()?
+                .metadata
+                .ok_or(NotSpecified("package.metadata"))?
+                .to_string(),
+
+            cli::Command::WorkspaceMembers => self.workspace()?.members.join(&delim_string),
+
+            cli::Command::WorkspaceDefaultMembers => {
+                self.workspace()?.default_members.join(&delim_string)
+            }
+
+            cli::Command::WorkspaceDependencies { inner } => {
+                let root_manifest = self.workspace_root()?;
+                let root = self.source.manifest(&root_manifest)?;
+                inner.match_dependencies(&self.source, &root_manifest, &root, delimiter)?
+            }
+
+            cli::Command::WorkspaceLints => {
+                let lints = self.workspace()?.lints;
+                if lints.is_empty() {
+                    return Err(NotSpecified("workspace.lints").into());
+                }
+                lints::render(&lints).join(&delim_string)
+            }
+
+            cli::Command::WorkspaceMetadata { key } => {
+                let metadata = self
+                    .workspace()?
+                    .metadata
+                    .ok_or(NotSpecified("workspace.metadata"))?;
+                let value = match key {
+                    Some(key) => workspace::lookup(&metadata, key).ok_or_else(|| {
+                        format!("`workspace.metadata.{key}` not specified in manifest")
+                    })?,
+                    None => &metadata,
+                };
+                workspace::render_value(value).join(&delim_string)
+            }
+
+            cli::Command::WorkspaceRoot => self
+                .workspace_root()?
+                .parent()
+                .ok_or(NotSpecified("workspace"))?
+                .to_string_lossy()
+                .to_string(),
+
+            cli::Command::WorkspacePackageVersion { inner } => {
+                let v: semver::Version = self
+                    .ws_package()?
+                    .version
+                    .ok_or(NotSpecified("workspace.package.version"))?
+                    .parse()
+                    .map_err(InvalidSemver)?;
+                inner.match_version(v, delimiter)?
+            }
+
+            cli::Command::WorkspacePackageAuthors { inner } => inner.match_authors(
+                &self
+                    .ws_package()?
+                    .authors
+                    .ok_or(NotSpecified("workspace.package.authors"))?,
+                delimiter,
+            ),
+
+            cli::Command::WorkspacePackageEdition => self
+                .ws_package()?
+                .edition
+                .map(|edition| edition.to_string())
+                .ok_or(NotSpecified("workspace.package.edition"))?
+                .to_string(),
+
+            cli::Command::WorkspacePackageHomepage => self
+                .ws_package()?
+                .homepage
+                .ok_or(NotSpecified("workspace.package.homepage"))?,
+
+            cli::Command::WorkspacePackageKeywords => self
+                .ws_package()?
+                .keywords
+                .ok_or(NotSpecified("workspace.package.keywords"))?
+                .join(&delim_string),
+
+            cli::Command::WorkspacePackageLicense { inner } => inner.match_license(
+                &self
+                    .ws_package()?
+                    .license
+                    .ok_or(NotSpecified("workspace.package.license"))?,
+                delimiter,
+            )?,
+
+            cli::Command::WorkspacePackageDescription => self
+                .ws_package()?
+                .description
+                .ok_or(NotSpecified("workspace.package.license"))?,
+
+            cli::Command::WorkspacePackageCategories => self
+                .ws_package()?
+                .categories
+                .ok_or(NotSpecified("workspace.package.categories"))?
+                .join(&delim_string),
+            cli::Command::WorkspacePackageDocumentation => self
+                .ws_package()?
+                .documentation
+                .ok_or(NotSpecified("workspace.package.documentation"))?,
+
+            cli::Command::WorkspacePackageExclude => self
+                .ws_package()?
+                .exclude
+                .ok_or(NotSpecified("workspace.package.exclude"))?
+                .join(&delim_string),
+
+            cli::Command::WorkspacePackageInclude => self
+                .ws_package()?
+                .include
+                .ok_or(NotSpecified("workspace.package.include"))?
+                .join(&delim_string),
+
+            cli::Command::WorkspacePackageLicenseFile => self
+                .ws_package()?
+                .license_file
+                .ok_or(NotSpecified("workspace.package.license_file"))?
+                .to_string_lossy()
+                .to_string(),
+
+            cli::Command::WorkspacePackagePublish => match self.ws_package()?.publish {
+                cargo_toml::Publish::Flag(flag) => flag.to_string(),
+                cargo_toml::Publish::Registry(list) => list.join(&delim_string),
+            },
+            cli::Command::WorkspacePackageReadme => self
+                .ws_package()?
+                .readme
+                .as_path()
+                .ok_or(NotSpecified("workspace.package.readme"))?
+                .to_string_lossy()
+                .to_string(),
+
+            cli::Command::WorkspacePackageRepository => self
+                .ws_package()?
+                .repository
+                .ok_or(NotSpecified("workspace.package.repository"))?,
+
+            cli::Command::WorkspacePackageRustVersion => self
+                .ws_package()?
+                .rust_version
+                .ok_or(NotSpecified("workspace.package.rust_version"))?
+                .to_string(),
+        };
+
+        Ok(output)
+    }
+}
+
+/// Search the given directory for Cargo.toml, recursively searching upwards
+fn search_manifest_path(source: &Source, dir: &Path) -> Option<PathBuf> {
+    let manifest = dir.join("Cargo.toml");
+
+    if source.is_file(&manifest) {
+        Some(manifest)
+    } else {
+        dir.parent()
+            .and_then(|parent| search_manifest_path(source, parent))
+    }
+}
@@ -0,0 +1,376 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use cargo_toml::Value;
+
+use crate::{authors::Author, context::Context, error::NotSpecified, spdx, workspace};
+
+/// The package fields of a Debian binary package, with `[package.metadata.deb]` applied
+#[derive(Debug, PartialEq)]
+pub struct Debian {
+    pub name: String,
+    pub upstream_name: String,
+    pub version: String,
+    /// `any` unless overridden, the package is built for each architecture
+    pub architecture: String,
+    pub maintainer: String,
+    pub description: String,
+    pub extended_description: Option<String>,
+    pub homepage: Option<String>,
+    /// Where the upstream source can be found
+    pub source: Option<String>,
+    pub section: Option<String>,
+    pub priority: Option<String>,
+    pub depends: Option<String>,
+    pub copyright: Vec<String>,
+    pub license: Option<String>,
+    /// Texts of the licenses Debian does not ship, by license name
+    pub license_texts: BTreeMap<String, String>,
+}
+
+impl Debian {
+    pub fn new(ctx: &Context) -> Result<Self, Box<dyn Error>> {
+        let package = ctx.package()?;
+        let metadata = package
+            .metadata
+            .as_ref()
+            .and_then(|metadata| workspace::lookup(metadata, "deb"));
+        let field = |key: &str| {
+            metadata
+                .and_then(|deb| workspace::lookup(deb, key))
+                .and_then(Value::as_str)
+                .map(str::to_owned)
+        };
+
+        let name = field("name").unwrap_or_else(|| package_name(package.name()));
+        let version = match field("revision").unwrap_or_else(|| "1".to_owned()) {
+            revision if revision.is_empty() => upstream_version(package.version()),
+            revision => format!("{}-{revision}", upstream_version(package.version())),
+        };
+        let maintainer = match (field("maintainer"), package.authors().first()) {
+            (Some(maintainer), _) => {
+                parse_maintainer(&maintainer, "package.metadata.deb.maintainer")?
+            }
+            (None, Some(author)) => parse_maintainer(author, "package.authors")?,
+            (None, None) => return Err(NotSpecified("package.authors").into()),
+        };
+        let description = package
+            .description()
+            .ok_or(NotSpecified("package.description"))?
+            .to_owned();
+
+        let dir = ctx.manifest_path.parent().ok_or("Invalid manifest path")?;
+        let extended_description = match (
+            field("extended-description"),
+            field("extended-description-file"),
+            package.readme().as_path(),
+        ) {
+            (Some(text), _, _) => Some(text),
+            (None, Some(file), _) => Some(ctx.source.read_to_string(&dir.join(file))?),
+            (None, None, Some(readme)) if ctx.source.is_file(&dir.join(readme)) => {
+                Some(ctx.source.read_to_string(&dir.join(readme))?)
+            }
+            (None, None, _) => None,
+        };
+
+        let depends = match metadata.and_then(|deb| workspace::lookup(deb, "depends")) {
+            Some(Value::Array(items)) => Some(
+                items
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            Some(value) => value.as_str().map(str::to_owned),
+            None => None,
+        };
+
+        let copyright = match field("copyright") {
+            Some(copyright) => vec![copyright],
+            None => package.authors().to_vec(),
+        };
+
+        let license = package.license().map(dep5_license);
+        let names = license.as_deref().map(license_names).unwrap_or_default();
+        let mut license_texts = BTreeMap::new();
+        for name in &names {
+            let file = license_files(name, names.len() == 1, package.license_file())
+                .into_iter()
+                .map(|file| dir.join(file))
+                .find(|path| ctx.source.is_file(path));
+            if let Some(file) = file {
+                license_texts.insert(name.clone(), ctx.source.read_to_string(&file)?);
+            }
+        }
+
+        Ok(Self {
+            name,
+            upstream_name: package.name().to_owned(),
+            version,
+            architecture: field("architecture").unwrap_or_else(|| "any".to_owned()),
+            maintainer,
+            description,
+            extended_description,
+            homepage: package
+                .homepage()
+                .or_else(|| package.repository())
+                .map(str::to_owned),
+            source: package
+                .repository()
+                .or_else(|| package.homepage())
+                .map(str::to_owned),
+            section: field("section"),
+            priority: field("priority"),
+            depends,
+            copyright,
+            license,
+            license_texts,
+        })
+    }
+
+    /// The binary package stanza of `debian/control`
+    pub fn control(&self) -> String {
+        let fields = [
+            ("Package", Some(&self.name)),
+            ("Version", Some(&self.version)),
+            ("Architecture", Some(&self.architecture)),
+            ("Section", self.section.as_ref()),
+            ("Priority", self.priority.as_ref()),
+            ("Maintainer", Some(&self.maintainer)),
+            ("Depends", self.depends.as_ref()),
+            ("Homepage", self.homepage.as_ref()),
+            ("Description", Some(&self.description)),
+        ];
+        let mut lines: Vec<_> = fields
+            .iter()
+            .filter_map(|(key, value)| Some(format!("{key}: {}", (*value)?)))
+            .collect();
+        if let Some(text) = &self.extended_description {
+            lines.extend(extended_description(text));
+        }
+
+        lines.join("\n")
+    }
+
+    /// A machine-readable `debian/copyright` file (DEP-5) covering all files of the package
+    pub fn copyright(&self) -> Result<String, Box<dyn Error>> {
+        let license = self
+            .license
+            .as_ref()
+            .ok_or(NotSpecified("package.license"))?;
+        let (first, rest) = self
+            .copyright
+            .split_first()
+            .ok_or(NotSpecified("package.authors"))?;
+
+        let mut lines = vec![
+            "Format: https://www.debian.org/doc/packaging-manuals/copyright-format/1.0/".to_owned(),
+            format!("Upstream-Name: {}", self.upstream_name),
+        ];
+        if let Some(source) = &self.source {
+            lines.push(format!("Source: {source}"));
+        }
+
+        lines.push(String::new());
+        lines.push("Files: *".to_owned());
+        lines.push(format!("Copyright: {first}"));
+        lines.extend(rest.iter().map(|holder| format!(" {holder}")));
+        lines.push(format!("License: {license}"));
+
+        for name in license_names(license) {
+            lines.push(String::new());
+            lines.push(format!("License: {name}"));
+            lines.push(self.license_text(&name)?);
+        }
+
+        Ok(lines.join("\n"))
+    }
+
+    /// A pointer to `/usr/share/common-licenses` where Debian ships the text, the text found
+    /// next to the manifest otherwise
+    fn license_text(&self, name: &str) -> Result<String, Box<dyn Error>> {
+        if let Some(file) = common_license(name) {
+            return Ok([
+                " On Debian systems, the complete text of this license can be found in".to_owned(),
+                format!(" \"/usr/share/common-licenses/{file}\"."),
+            ]
+            .join("\n"));
+        }
+
+        match self.license_texts.get(name) {
+            Some(text) => Ok(extended_description(text).join("\n")),
+            None => Err(format!(
+                "No license text for {name}, add a LICENSE-{} file next to Cargo.toml",
+                license_family(name)
+            )
+            .into()),
+        }
+    }
+}
+
+/// Debian package names are lower case and may not contain underscores
+fn package_name(name: &str) -> String {
+    name.to_lowercase().replace('_', "-")
+}
+
+/// Pre-releases sort before the release in Debian only when separated with `~`
+pub fn upstream_version(version: &str) -> String {
+    let Ok(version) = semver::Version::parse(version) else {
+        return version.to_owned();
+    };
+
+    let mut upstream = format!("{}.{}.{}", version.major, version.minor, version.patch);
+    if !version.pre.is_empty() {
+        upstream.push_str(&format!("~{}", version.pre));
+    }
+    if !version.build.is_empty() {
+        upstream.push_str(&format!("+{}", version.build));
+    }
+    upstream
+}
+
+/// DEP-5 spells the SPDX operators in lower case
+fn dep5_license(license: &str) -> String {
+    spdx::normalize_or_raw(license)
+        .replace(" OR ", " or ")
+        .replace(" AND ", " and ")
+        .replace(" WITH ", " with ")
+}
+
+/// The names a DEP-5 `License:` line is made of, each needs a standalone license paragraph
+fn license_names(license: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for name in license
+        .replace(['(', ')'], "")
+        .split(" or ")
+        .flat_map(|name| name.split(" and "))
+    {
+        let name = name.trim().to_owned();
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// The file in `/usr/share/common-licenses` with the text of license `name`
+fn common_license(name: &str) -> Option<&str> {
+    let id = name.split(" with ").next().unwrap_or(name);
+    match id {
+        "Apache-2.0" | "CC0-1.0" | "MPL-1.1" | "MPL-2.0" => Some(id),
+        "GPL-2.0" | "GPL-2.0-only" | "GPL-2.0-or-later" => Some("GPL-2"),
+        "GPL-3.0" | "GPL-3.0-only" | "GPL-3.0-or-later" => Some("GPL-3"),
+        "LGPL-2.1" | "LGPL-2.1-only" | "LGPL-2.1-or-later" => Some("LGPL-2.1"),
+        "LGPL-3.0" | "LGPL-3.0-only" | "LGPL-3.0-or-later" => Some("LGPL-3"),
+        _ => None,
+    }
+}
+
+/// `MIT` for `MIT`, `APACHE` for `Apache-2.0`, as in the usual `LICENSE-APACHE`
+fn license_family(name: &str) -> String {
+    name.split(['-', ' ']).next().unwrap_or(name).to_uppercase()
+}
+
+/// Files that may hold the text of license `name`, e.g. `LICENSE-MIT` or `LICENSES/MIT.txt`.
+/// `package.license-file`, `LICENSE` and `COPYING` only count when there is one license.
+fn license_files(name: &str, only: bool, license_file: Option<&Path>) -> Vec<PathBuf> {
+    let id = name.split(" with ").next().unwrap_or(name);
+    let mut files = Vec::new();
+    for stem in [
+        format!("LICENSE-{id}"),
+        format!("LICENSE-{}", license_family(id)),
+    ] {
+        for extension in ["", ".md", ".txt"] {
+            files.push(PathBuf::from(format!("{stem}{extension}")));
+        }
+    }
+    files.push(PathBuf::from(format!("LICENSES/{id}.txt")));
+
+    if only {
+        files.extend(license_file.map(Path::to_path_buf));
+        files.extend(["LICENSE", "LICENSE.md", "LICENSE.txt", "COPYING"].map(PathBuf::from));
+    }
+    files
+}
+
+/// `Name <email>` as Debian requires it
+fn parse_maintainer(value: &str, key: &'static str) -> Result<String, NotSpecified> {
+    match Author::parse(value) {
+        Author {
+            name: Some(name),
+            email: Some(email),
+        } => Ok(format!("{name} <{email}>")),
+        _ => Err(NotSpecified(key)),
+    }
+}
+
+/// Continuation lines start with a space and empty lines are written as ` .`
+fn extended_description(text: &str) -> Vec<String> {
+    text.trim()
+        .lines()
+        .map(|line| match line.trim_end() {
+            "" => " .".to_owned(),
+            line => format!(" {line}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn upstream_version_ok() {
+        assert_eq!(upstream_version("1.2.3"), "1.2.3");
+        assert_eq!(upstream_version("1.2.3-alpha.1"), "1.2.3~alpha.1");
+        assert_eq!(upstream_version("1.2.3-rc.1+build.5"), "1.2.3~rc.1+build.5");
+        assert_eq!(upstream_version("1.2"), "1.2");
+    }
+
+    #[test]
+    fn dep5_license_ok() {
+        assert_eq!(dep5_license("MIT/Apache-2.0"), "MIT or Apache-2.0");
+        assert_eq!(
+            dep5_license("GPL-2.0-or-later WITH Classpath-exception-2.0 AND MIT"),
+            "MIT and GPL-2.0-or-later with Classpath-exception-2.0"
+        );
+    }
+
+    #[test]
+    fn license_names_ok() {
+        assert_eq!(
+            license_names("MIT and (GPL-2.0-or-later with Classpath-exception-2.0 or MIT)"),
+            vec!["MIT", "GPL-2.0-or-later with Classpath-exception-2.0"]
+        );
+    }
+
+    #[test]
+    fn parse_maintainer_ok() {
+        assert_eq!(
+            parse_maintainer("John Doe<john-doe@abc.com>", "package.authors").unwrap(),
+            "John Doe <john-doe@abc.com>"
+        );
+        assert!(parse_maintainer("ACME Corp.", "package.authors").is_err());
+        assert!(parse_maintainer("john-doe@abc.com", "package.authors").is_err());
+    }
+
+    #[test]
+    fn license_files_ok() {
+        let files = license_files("Apache-2.0", false, Some(Path::new("LICENSE")));
+        assert!(files.contains(&PathBuf::from("LICENSE-APACHE")));
+        assert!(files.contains(&PathBuf::from("LICENSE-Apache-2.0.txt")));
+        assert!(!files.contains(&PathBuf::from("LICENSE")));
+        assert!(license_files("MIT", true, None).contains(&PathBuf::from("COPYING")));
+    }
+
+    #[test]
+    fn extended_description_ok() {
+        assert_eq!(
+            extended_description("\n# Title\n\nSome text.  \n"),
+            vec![" # Title", " .", " Some text."]
+        );
+    }
+}
@@ -1,10 +1,11 @@
 use std::fmt;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Default)]
 pub enum Delimiter {
     Tab,
     Cr,
     Lf,
+    #[default]
     CrLf,
     String(String),
 }
@@ -35,12 +36,6 @@ impl std::str::FromStr for Delimiter {
     }
 }
 
-impl Default for Delimiter {
-    fn default() -> Self {
-        Self::CrLf
-    }
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
@@ -0,0 +1,282 @@
+use std::{collections::BTreeSet, error::Error, fmt, path::Path};
+
+use strum::IntoEnumIterator;
+
+use crate::{cli::Command, context::Context, delimiter::Delimiter, git, source::Source};
+
+/// How far apart two versions are
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Bump {
+    Build,
+    Pre,
+    Patch,
+    Minor,
+    Major,
+}
+
+impl fmt::Display for Bump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Build => "build",
+            Self::Pre => "pre",
+            Self::Patch => "patch",
+            Self::Minor => "minor",
+            Self::Major => "major",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl Bump {
+    /// The most significant component that differs, `None` for equal or unparsable versions.
+    /// Like Cargo, the leftmost non-zero component is the major one, so `0.1.0 -> 0.2.0` is
+    /// a major and `0.1.0 -> 0.1.1` a minor bump.
+    pub fn between(from: &str, to: &str) -> Option<Self> {
+        let from: semver::Version = from.parse().ok()?;
+        let to: semver::Version = to.parse().ok()?;
+
+        let (major, minor) = match (from.major, from.minor) {
+            (0, 0) => (Self::Major, Self::Major),
+            (0, _) => (Self::Major, Self::Minor),
+            _ => (Self::Minor, Self::Patch),
+        };
+
+        if from.major != to.major {
+            Some(Self::Major)
+        } else if from.minor != to.minor {
+            Some(major)
+        } else if from.patch != to.patch {
+            Some(minor)
+        } else if from.pre != to.pre {
+            Some(Self::Pre)
+        } else if from.build != to.build {
+            Some(Self::Build)
+        } else {
+            None
+        }
+    }
+}
+
+/// A single difference between two manifests
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    Added {
+        field: String,
+        value: String,
+    },
+    Removed {
+        field: String,
+        value: String,
+    },
+    Changed {
+        field: String,
+        from: String,
+        to: String,
+        bump: Option<Bump>,
+    },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Added { field, value } => write!(f, "+ {field} {value}"),
+            Self::Removed { field, value } => write!(f, "- {field} {value}"),
+            Self::Changed {
+                field,
+                from,
+                to,
+                bump: Some(bump),
+            } => write!(f, "~ {field} {from} -> {to} ({bump})"),
+            Self::Changed {
+                field, from, to, ..
+            } => write!(f, "~ {field} {from} -> {to}"),
+        }
+    }
+}
+
+impl Change {
+    pub fn field(&self) -> &str {
+        match self {
+            Self::Added { field, .. }
+            | Self::Removed { field, .. }
+            | Self::Changed { field, .. } => field,
+        }
+    }
+
+    /// Whether the change is matched by a `--fail-on` rule: `any`, `version`, a minimum
+    /// version bump (`major`, `minor`, `patch`) or a field name or prefix
+    pub fn matches(&self, rule: &str) -> bool {
+        let bump = match self {
+            Self::Changed { bump, .. } => *bump,
+            _ => None,
+        };
+
+        match rule {
+            "any" => true,
+            "version" => is_version(self.field()),
+            "major" => bump >= Some(Bump::Major),
+            "minor" => bump >= Some(Bump::Minor),
+            "patch" => bump >= Some(Bump::Patch),
+            field => {
+                self.field() == field
+                    || self
+                        .field()
+                        .strip_prefix(field)
+                        .is_some_and(|rest| rest.starts_with('.'))
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UnexpectedChanges(pub Vec<String>);
+
+impl fmt::Display for UnexpectedChanges {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unexpected manifest changes:")?;
+        for change in &self.0 {
+            write!(f, "\n  {change}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for UnexpectedChanges {}
+
+/// Read one side of a diff. Paths that exist relative to `entry` are read from the working
+/// tree, anything else is taken as a git revision of `entry`.
+pub fn open(side: &str, entry: &Path) -> Result<Context, Box<dyn Error>> {
+    let dir = match entry.is_file() {
+        true => entry.parent().unwrap_or(entry),
+        false => entry,
+    };
+
+    let path = dir.join(side);
+    if path.exists() {
+        return Context::load(Source::WorkingTree, &std::fs::canonicalize(path)?);
+    }
+
+    let revision = git::Revision::open(dir, side)?;
+    Context::load(Source::Git(revision), entry)
+}
+
+/// Compare every field `cargo get` can print, except the workspace root path
+pub fn changes(a: &Context, b: &Context) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    for cmd in Command::iter().filter(|cmd| !matches!(cmd, Command::WorkspaceRoot)) {
+        let field = cmd.field_name();
+        let (old, new) = (value(a, &cmd), value(b, &cmd));
+
+        if is_list(&cmd) {
+            let old: BTreeSet<_> = old.iter().flat_map(|v| v.lines()).collect();
+            let new: BTreeSet<_> = new.iter().flat_map(|v| v.lines()).collect();
+            changes.extend(old.difference(&new).map(|value| Change::Removed {
+                field: field.clone(),
+                value: value.to_string(),
+            }));
+            changes.extend(new.difference(&old).map(|value| Change::Added {
+                field: field.clone(),
+                value: value.to_string(),
+            }));
+            continue;
+        }
+
+        match (old, new) {
+            (Some(value), None) => changes.push(Change::Removed { field, value }),
+            (None, Some(value)) => changes.push(Change::Added { field, value }),
+            (Some(from), Some(to)) if from != to => {
+                let bump = match is_version(&field) {
+                    true => Bump::between(&from, &to),
+                    false => None,
+                };
+                changes.push(Change::Changed {
+                    field,
+                    from,
+                    to,
+                    bump,
+                })
+            }
+            _ => {}
+        }
+    }
+
+    changes
+}
+
+/// The value as written in the manifest, one item per line for arrays
+fn value(ctx: &Context, cmd: &Command) -> Option<String> {
+    ctx.get(cmd, &Delimiter::Lf).ok()
+}
+
+fn is_version(field: &str) -> bool {
+    matches!(field, "package.version" | "workspace.package.version")
+}
+
+/// Fields compared as sets, ignoring order
+fn is_list(cmd: &Command) -> bool {
+    matches!(
+        cmd,
+        Command::PackageAuthors { .. }
+            | Command::PackageKeywords
+            | Command::PackageCategories
+            | Command::PackageExclude
+            | Command::PackageInclude
+            | Command::PackagePublish
+            | Command::WorkspaceMembers
+            | Command::WorkspaceDefaultMembers
+            | Command::WorkspaceDependencies { .. }
+            | Command::WorkspaceLints
+            | Command::WorkspaceMetadata { .. }
+            | Command::WorkspacePackageAuthors { .. }
+            | Command::WorkspacePackageCategories
+            | Command::WorkspacePackageExclude
+            | Command::WorkspacePackageInclude
+            | Command::WorkspacePackageKeywords
+            | Command::WorkspacePackagePublish
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bump_ok() {
+        assert_eq!(Bump::between("1.2.3", "2.0.0"), Some(Bump::Major));
+        assert_eq!(Bump::between("1.2.3", "1.3.0"), Some(Bump::Minor));
+        assert_eq!(Bump::between("1.2.3", "1.2.2"), Some(Bump::Patch));
+        assert_eq!(Bump::between("1.2.3-alpha", "1.2.3"), Some(Bump::Pre));
+        assert_eq!(Bump::between("1.2.3", "1.2.3+build"), Some(Bump::Build));
+        assert_eq!(Bump::between("1.2.3", "1.2.3"), None);
+        assert_eq!(Bump::between("1.2", "1.3"), None);
+    }
+
+    #[test]
+    fn bump_zero_major() {
+        assert_eq!(Bump::between("0.1.0", "0.2.0"), Some(Bump::Major));
+        assert_eq!(Bump::between("0.1.0", "0.1.1"), Some(Bump::Minor));
+        assert_eq!(Bump::between("0.0.1", "0.0.2"), Some(Bump::Major));
+        assert_eq!(Bump::between("0.9.3", "1.0.0"), Some(Bump::Major));
+        assert_eq!(Bump::between("0.1.0-alpha", "0.1.0"), Some(Bump::Pre));
+    }
+
+    #[test]
+    fn matches_ok() {
+        let change = Change::Changed {
+            field: "package.version".into(),
+            from: "1.2.3".into(),
+            to: "1.3.0".into(),
+            bump: Some(Bump::Minor),
+        };
+
+        assert!(change.matches("any"));
+        assert!(change.matches("version"));
+        assert!(change.matches("patch"));
+        assert!(change.matches("minor"));
+        assert!(!change.matches("major"));
+        assert!(change.matches("package"));
+        assert!(change.matches("package.version"));
+        assert!(!change.matches("package.ver"));
+    }
+}
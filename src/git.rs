@@ -0,0 +1,176 @@
+use std::{
+    collections::HashSet,
+    error::Error,
+    fmt, io,
+    path::{Component, Path, PathBuf},
+    process::Command,
+};
+
+use cargo_toml::AbstractFilesystem;
+
+#[derive(Debug)]
+pub struct UnknownRevision(pub String);
+
+impl fmt::Display for UnknownRevision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown git revision `{}`", self.0)
+    }
+}
+
+impl Error for UnknownRevision {}
+
+#[derive(Debug)]
+pub struct NotInRevision {
+    pub path: String,
+    pub rev: String,
+}
+
+impl fmt::Display for NotInRevision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` does not exist at revision `{}`",
+            self.path, self.rev
+        )
+    }
+}
+
+impl Error for NotInRevision {}
+
+/// A commit in the local repository. Files are read from the object database with the `git`
+/// command, without touching the working tree or the network.
+#[derive(Debug)]
+pub struct Revision {
+    toplevel: PathBuf,
+    commit: String,
+    rev: String,
+}
+
+fn git(dir: &Path, args: &[&str]) -> io::Result<Option<Vec<u8>>> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+    Ok(output.status.success().then_some(output.stdout))
+}
+
+fn text(bytes: Vec<u8>) -> String {
+    String::from_utf8_lossy(&bytes).trim().to_owned()
+}
+
+/// The root of the working tree containing `dir`, if it is in a git repository
+pub fn toplevel(dir: &Path) -> Option<PathBuf> {
+    let toplevel = git(dir, &["rev-parse", "--show-toplevel"]).ok()??;
+    std::fs::canonicalize(text(toplevel)).ok()
+}
+
+impl Revision {
+    /// Resolve `rev` in the repository containing `dir`
+    pub fn open(dir: &Path, rev: &str) -> Result<Self, Box<dyn Error>> {
+        let toplevel = git(dir, &["rev-parse", "--show-toplevel"])
+            .map_err(|err| format!("Failed to run git: {err}"))?
+            .ok_or_else(|| format!("Not a git repository: {}", dir.display()))?;
+        let toplevel = std::fs::canonicalize(text(toplevel))?;
+
+        let commit = git(
+            &toplevel,
+            &[
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{rev}^{{commit}}"),
+            ],
+        )?
+        .ok_or_else(|| UnknownRevision(rev.to_owned()))?;
+
+        Ok(Self {
+            toplevel,
+            commit: text(commit),
+            rev: rev.to_owned(),
+        })
+    }
+
+    /// `<commit>:<path>` with the path relative to the repository root
+    fn object(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.toplevel).ok()?;
+        let parts: Vec<_> = relative
+            .components()
+            .map(|c| match c {
+                Component::Normal(s) => Some(s.to_string_lossy()),
+                _ => None,
+            })
+            .collect::<Option<_>>()?;
+        Some(format!("{}:{}", self.commit, parts.join("/")))
+    }
+
+    fn kind(&self, path: &Path) -> Option<String> {
+        let object = self.object(path)?;
+        git(&self.toplevel, &["cat-file", "-t", &object])
+            .ok()
+            .flatten()
+            .map(text)
+    }
+
+    pub fn is_file(&self, path: &Path) -> bool {
+        self.kind(path).as_deref() == Some("blob")
+    }
+
+    pub fn is_dir(&self, path: &Path) -> bool {
+        self.kind(path).as_deref() == Some("tree")
+    }
+
+    pub fn not_found(&self, path: &Path) -> NotInRevision {
+        let path = path.strip_prefix(&self.toplevel).unwrap_or(path);
+        NotInRevision {
+            path: path.to_string_lossy().to_string(),
+            rev: self.rev.clone(),
+        }
+    }
+
+    pub fn read_to_string(&self, path: &Path) -> Result<String, Box<dyn Error>> {
+        let object = self.object(path).ok_or_else(|| self.not_found(path))?;
+        let content = git(&self.toplevel, &["cat-file", "blob", &object])?
+            .ok_or_else(|| self.not_found(path))?;
+        Ok(String::from_utf8(content)?)
+    }
+
+    /// Names of the entries in a directory and whether they are directories themselves
+    pub fn entries(&self, dir: &Path) -> io::Result<Vec<(String, bool)>> {
+        let object = self
+            .object(dir)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+        let listing = git(&self.toplevel, &["ls-tree", "-z", &object])?
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+
+        Ok(listing
+            .split(|b| *b == 0)
+            .filter_map(|entry| {
+                let entry = String::from_utf8_lossy(entry);
+                let (meta, name) = entry.split_once('\t')?;
+                let is_dir = meta.split(' ').nth(1) == Some("tree");
+                Some((name.to_owned(), is_dir))
+            })
+            .collect())
+    }
+
+    /// The directory of a manifest at this revision, for lib and bin autodiscovery
+    pub fn dir(&self, dir: &Path) -> RevisionDir<'_> {
+        RevisionDir {
+            revision: self,
+            dir: dir.to_owned(),
+        }
+    }
+}
+
+pub struct RevisionDir<'a> {
+    revision: &'a Revision,
+    dir: PathBuf,
+}
+
+impl AbstractFilesystem for RevisionDir<'_> {
+    fn file_names_in(&self, rel_path: &str) -> io::Result<HashSet<Box<str>>> {
+        Ok(self
+            .revision
+            .entries(&self.dir.join(rel_path))?
+            .into_iter()
+            .map(|(name, _)| name.into())
+            .collect())
+    }
+}
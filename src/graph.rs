@@ -0,0 +1,426 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    error::Error,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use cargo_toml::{Dependency, DepsSet, Inheritable, Manifest, Publish};
+use clap::ValueEnum;
+
+use crate::{error::NotSpecified, source::Source, workspace};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Kind {
+    Normal,
+    Build,
+    Dev,
+}
+
+/// A path dependency from one workspace member on another
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub kind: Kind,
+}
+
+impl fmt::Display for Edge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {}", self.from, self.to)?;
+        match self.kind {
+            Kind::Normal => Ok(()),
+            Kind::Build => write!(f, " (build)"),
+            Kind::Dev => write!(f, " (dev)"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, ValueEnum)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+}
+
+#[derive(Debug, PartialEq, Clone, Default, ValueEnum)]
+pub enum AffectedFormat {
+    #[default]
+    Names,
+    Paths,
+    /// `{"include":[{"name":…,"path":…}]}` for a GitHub Actions matrix
+    Matrix,
+}
+
+#[derive(Debug)]
+pub struct Cycle(pub Vec<String>);
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Dependency cycle between workspace members: {}",
+            self.0.join(" -> ")
+        )
+    }
+}
+
+impl Error for Cycle {}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Member {
+    pub dir: PathBuf,
+    pub publish: bool,
+}
+
+/// Path dependencies between the members of a workspace
+#[derive(Debug, Default)]
+pub struct Graph {
+    pub root_dir: PathBuf,
+    pub members: BTreeMap<String, Member>,
+    pub edges: Vec<Edge>,
+}
+
+impl Graph {
+    pub fn load(
+        source: &Source,
+        root_manifest: &Path,
+        root: &Manifest,
+    ) -> Result<Self, Box<dyn Error>> {
+        let workspace = root.workspace.as_ref().ok_or(NotSpecified("workspace"))?;
+        let mut manifests = Vec::new();
+
+        for dir in workspace::members(source, root_manifest, root) {
+            let manifest = source.raw_manifest(&dir.join("Cargo.toml"))?;
+            if manifest.package.is_some() {
+                manifests.push((dir, manifest));
+            }
+        }
+
+        let mut graph = Self {
+            root_dir: root_manifest.parent().unwrap_or(Path::new("")).to_owned(),
+            ..Default::default()
+        };
+        for (dir, manifest) in &manifests {
+            let package = manifest.package.as_ref().expect("filtered above");
+            let publish = match &package.publish {
+                Inheritable::Set(publish) => publish.clone(),
+                Inheritable::Inherited => workspace
+                    .package
+                    .as_ref()
+                    .map(|p| p.publish.clone())
+                    .unwrap_or_default(),
+            };
+            let publish = match publish {
+                Publish::Flag(flag) => flag,
+                Publish::Registry(registries) => !registries.is_empty(),
+            };
+            let member = Member {
+                dir: dir.clone(),
+                publish,
+            };
+            graph.members.insert(package.name().to_owned(), member);
+        }
+
+        for (_, manifest) in &manifests {
+            let from = manifest.package.as_ref().expect("filtered above").name();
+
+            let mut sets: Vec<(&DepsSet, Kind)> = vec![
+                (&manifest.dependencies, Kind::Normal),
+                (&manifest.build_dependencies, Kind::Build),
+                (&manifest.dev_dependencies, Kind::Dev),
+            ];
+            for target in manifest.target.values() {
+                sets.extend([
+                    (&target.dependencies, Kind::Normal),
+                    (&target.build_dependencies, Kind::Build),
+                    (&target.dev_dependencies, Kind::Dev),
+                ]);
+            }
+
+            for (set, kind) in sets {
+                for (name, dep) in set {
+                    let detail = match dep {
+                        Dependency::Inherited(_) => workspace.dependencies.get(name),
+                        dep => Some(dep),
+                    }
+                    .and_then(Dependency::detail);
+
+                    let Some(detail) = detail.filter(|d| d.path.is_some()) else {
+                        continue;
+                    };
+                    let to = detail.package.as_deref().unwrap_or(name);
+                    let edge = Edge {
+                        from: from.to_owned(),
+                        to: to.to_owned(),
+                        kind,
+                    };
+                    if graph.members.contains_key(to) && !graph.edges.contains(&edge) {
+                        graph.edges.push(edge);
+                    }
+                }
+            }
+        }
+
+        graph.edges.sort();
+        Ok(graph)
+    }
+
+    fn dependencies<'a>(
+        &'a self,
+        name: &'a str,
+        with_dev: bool,
+    ) -> impl Iterator<Item = &'a str> + 'a {
+        self.edges
+            .iter()
+            .filter(move |e| e.from == name && (with_dev || e.kind != Kind::Dev))
+            .map(|e| e.to.as_str())
+    }
+
+    /// Members ordered so every member comes after its normal and build dependencies.
+    /// Unpublished members are skipped, dev-dependencies are ignored.
+    pub fn publish_order(&self) -> Result<Vec<String>, Cycle> {
+        if let Some(cycle) = self.cycles(false).into_iter().next() {
+            return Err(Cycle(cycle));
+        }
+
+        let mut done = BTreeSet::new();
+        let mut order = Vec::new();
+
+        while done.len() < self.members.len() {
+            let ready: Vec<&String> = self
+                .members
+                .keys()
+                .filter(|name| !done.contains(name.as_str()))
+                .filter(|name| self.dependencies(name, false).all(|dep| done.contains(dep)))
+                .collect();
+
+            for name in ready {
+                done.insert(name.as_str());
+                if self.members[name].publish {
+                    order.push(name.clone());
+                }
+            }
+        }
+
+        Ok(order)
+    }
+
+    /// Members owning one of the `changed` files, relative to `base`, and every member
+    /// depending on them, directly or transitively. A change to the root `Cargo.toml` or
+    /// `Cargo.lock` affects all members.
+    pub fn affected(&self, base: &Path, changed: &[PathBuf]) -> BTreeSet<&str> {
+        let mut affected = BTreeSet::new();
+
+        for path in changed {
+            let path = base.join(path);
+            let root_file = ["Cargo.toml", "Cargo.lock"]
+                .iter()
+                .any(|name| path == self.root_dir.join(name));
+            if root_file {
+                return self.members.keys().map(String::as_str).collect();
+            }
+
+            let owner = self
+                .members
+                .iter()
+                .filter(|(_, member)| path.starts_with(&member.dir))
+                .max_by_key(|(_, member)| member.dir.components().count());
+            if let Some((name, _)) = owner {
+                affected.insert(name.as_str());
+            }
+        }
+
+        let mut queue: Vec<&str> = affected.iter().copied().collect();
+        while let Some(name) = queue.pop() {
+            for edge in self.edges.iter().filter(|e| e.to == name) {
+                if affected.insert(edge.from.as_str()) {
+                    queue.push(&edge.from);
+                }
+            }
+        }
+
+        affected
+    }
+
+    /// Every distinct cycle, each starting and ending with the same member. With `dev`, the
+    /// cycles that are only possible through a dev-dependency.
+    pub fn cycles(&self, dev: bool) -> Vec<Vec<String>> {
+        let mut found: Vec<Vec<String>> = Vec::new();
+
+        for edge in &self.edges {
+            let starts_cycle = match dev {
+                true => edge.kind == Kind::Dev && !self.has_edge(&edge.from, &edge.to),
+                false => edge.kind != Kind::Dev,
+            };
+            if !starts_cycle {
+                continue;
+            }
+
+            let path = match edge.from == edge.to {
+                true => Some(vec![edge.to.clone()]),
+                false => self.path(&edge.to, &edge.from, dev),
+            };
+            if let Some(path) = path {
+                let mut cycle = vec![edge.from.clone()];
+                cycle.extend(path);
+
+                let key = rotate(&cycle);
+                if !found.iter().any(|c| rotate(c) == key) {
+                    found.push(cycle);
+                }
+            }
+        }
+
+        found
+    }
+
+    /// Whether `from` has a normal or build dependency on `to`
+    fn has_edge(&self, from: &str, to: &str) -> bool {
+        self.dependencies(from, false).any(|dep| dep == to)
+    }
+
+    /// Shortest path from `from` to `to` following at least one edge, both ends included
+    fn path(&self, from: &str, to: &str, with_dev: bool) -> Option<Vec<String>> {
+        let mut previous: BTreeMap<&str, &str> = BTreeMap::new();
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(current) = queue.pop_front() {
+            for dep in self.dependencies(current, with_dev) {
+                if previous.contains_key(dep) {
+                    continue;
+                }
+                previous.insert(dep, current);
+
+                if dep == to {
+                    let mut path = vec![to.to_owned()];
+                    let mut node = to;
+                    loop {
+                        node = previous[node];
+                        path.push(node.to_owned());
+                        if node == from {
+                            break;
+                        }
+                    }
+                    path.reverse();
+                    return Some(path);
+                }
+                queue.push_back(dep);
+            }
+        }
+
+        None
+    }
+
+    pub fn render(&self, format: &GraphFormat) -> String {
+        let mut lines = Vec::new();
+        match format {
+            GraphFormat::Dot => {
+                lines.push("digraph {".to_owned());
+                for name in self.members.keys() {
+                    lines.push(format!("    \"{name}\";"));
+                }
+                for edge in &self.edges {
+                    let style = match edge.kind {
+                        Kind::Normal => "",
+                        Kind::Build => " [style=dotted, label=\"build\"]",
+                        Kind::Dev => " [style=dashed, label=\"dev\"]",
+                    };
+                    lines.push(format!("    \"{}\" -> \"{}\"{style};", edge.from, edge.to));
+                }
+                lines.push("}".to_owned());
+            }
+            GraphFormat::Mermaid => {
+                let ids: BTreeMap<&str, String> = self
+                    .members
+                    .keys()
+                    .enumerate()
+                    .map(|(i, name)| (name.as_str(), format!("n{i}")))
+                    .collect();
+
+                lines.push("graph TD".to_owned());
+                for (name, id) in &ids {
+                    lines.push(format!("    {id}[\"{name}\"]"));
+                }
+                for edge in &self.edges {
+                    let arrow = match edge.kind {
+                        Kind::Normal => "-->",
+                        Kind::Build => "-.->|build|",
+                        Kind::Dev => "-.->|dev|",
+                    };
+                    let (from, to) = (&ids[edge.from.as_str()], &ids[edge.to.as_str()]);
+                    lines.push(format!("    {from} {arrow} {to}"));
+                }
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+/// A cycle rotated to start at its smallest member, to compare cycles regardless of start
+fn rotate(cycle: &[String]) -> Vec<String> {
+    let nodes = &cycle[..cycle.len() - 1];
+    let start = nodes
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, name)| *name)
+        .map(|(i, _)| i)
+        .unwrap_or_default();
+    nodes[start..]
+        .iter()
+        .chain(&nodes[..start])
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn graph(edges: &[(&str, &str, Kind)]) -> Graph {
+        let mut graph = Graph::default();
+        for (from, to, kind) in edges {
+            for name in [from, to] {
+                let member = Member {
+                    dir: PathBuf::from(name),
+                    publish: true,
+                };
+                graph.members.insert(name.to_string(), member);
+            }
+            graph.edges.push(Edge {
+                from: from.to_string(),
+                to: to.to_string(),
+                kind: *kind,
+            });
+        }
+        graph
+    }
+
+    #[test]
+    fn publish_order_ok() {
+        let graph = graph(&[
+            ("cli", "core", Kind::Normal),
+            ("core", "macros", Kind::Build),
+            ("macros", "cli", Kind::Dev),
+        ]);
+
+        assert_eq!(graph.publish_order().unwrap(), ["macros", "core", "cli"]);
+        assert!(graph.cycles(false).is_empty());
+        assert_eq!(graph.cycles(true), [["macros", "cli", "core", "macros"]]);
+    }
+
+    #[test]
+    fn publish_order_cycle() {
+        let graph = graph(&[
+            ("a", "b", Kind::Normal),
+            ("b", "c", Kind::Normal),
+            ("c", "a", Kind::Build),
+            ("c", "a", Kind::Dev),
+        ]);
+
+        assert_eq!(
+            graph.publish_order().unwrap_err().to_string(),
+            "Dependency cycle between workspace members: a -> b -> c -> a"
+        );
+        assert!(graph.cycles(true).is_empty());
+    }
+}
@@ -0,0 +1,194 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use cargo_toml::Manifest;
+
+use crate::{
+    lock::{Lockfile, SourceKind},
+    spdx,
+};
+
+/// What a crate declares in its unpacked manifest
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum License {
+    Expression(String),
+    File,
+    None,
+}
+
+impl fmt::Display for License {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Expression(expression) => write!(f, "{expression}"),
+            Self::File => write!(f, "license-file"),
+            Self::None => write!(f, "none"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Disallowed(pub Vec<String>);
+
+impl fmt::Display for Disallowed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Disallowed licenses:")?;
+        for item in &self.0 {
+            write!(f, "\n  {item}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for Disallowed {}
+
+/// Licenses of the locked registry packages, read from `$CARGO_HOME/registry/src`
+#[derive(Debug)]
+pub struct Report {
+    /// `name version` of the crates declaring each license
+    pub licenses: BTreeMap<License, Vec<String>>,
+    /// `name version` of the crates whose license could not be read, with the reason
+    pub missing: Vec<(String, &'static str)>,
+}
+
+impl Report {
+    /// Git dependencies are not unpacked in the registry cache and always reported as missing.
+    /// Path dependencies are workspace members or local crates and are skipped.
+    pub fn new(lockfile: &Lockfile, cargo_home: &Path) -> Result<Self, Box<dyn Error>> {
+        let registries = registries(cargo_home);
+        let mut licenses: BTreeMap<License, Vec<String>> = BTreeMap::new();
+        let mut missing = Vec::new();
+
+        for package in &lockfile.packages {
+            let label = format!("{} {}", package.name, package.version);
+            let hosts = match (package.source_kind(), package.source.as_deref()) {
+                (SourceKind::Path, _) | (_, None) => continue,
+                (SourceKind::Git, _) => {
+                    missing.push((label, "git dependency"));
+                    continue;
+                }
+                (SourceKind::CratesIo, _) => vec!["index.crates.io", "github.com"],
+                (SourceKind::Registry, Some(source)) => registry_host(source).into_iter().collect(),
+            };
+            let dir = format!("{}-{}", package.name, package.version);
+
+            let Some(path) = registries
+                .iter()
+                .filter(|registry| {
+                    registry
+                        .file_name()
+                        .and_then(|name| name.to_str()?.rsplit_once('-'))
+                        .is_some_and(|(host, _)| hosts.contains(&host))
+                })
+                .map(|registry| registry.join(&dir).join("Cargo.toml"))
+                .find(|path| path.is_file())
+            else {
+                missing.push((label, "not in the registry cache"));
+                continue;
+            };
+
+            let content = std::fs::read_to_string(&path)?;
+            let manifest = Manifest::from_str(&content)
+                .map_err(|err| format!("Failed to parse {}: {err}", path.display()))?;
+            let package = manifest
+                .package
+                .as_ref()
+                .ok_or_else(|| format!("{} has no [package] table", path.display()))?;
+
+            let license = match (package.license(), package.license_file()) {
+                (Some(license), _) => License::Expression(spdx::normalize_or_raw(license)),
+                (None, Some(_)) => License::File,
+                (None, None) => License::None,
+            };
+            licenses.entry(license).or_default().push(label);
+        }
+
+        Ok(Self { licenses, missing })
+    }
+
+    /// `license: crate version, ...` per license, followed by the crates missing from the cache
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines: Vec<_> = self
+            .licenses
+            .iter()
+            .map(|(license, crates)| format!("{license}: {}", crates.join(", ")))
+            .collect();
+        if !self.missing.is_empty() {
+            let missing: Vec<_> = self.missing.iter().map(|(c, _)| c.as_str()).collect();
+            lines.push(format!("missing: {}", missing.join(", ")));
+        }
+        lines
+    }
+
+    /// Crates that cannot be used with only the `allow`ed license ids, or that need a `deny`ed
+    /// one. Crates whose license cannot be determined are never accepted.
+    pub fn disallowed(&self, allow: &[String], deny: &[String]) -> Vec<String> {
+        let contains = |ids: &[String], id: &str| ids.iter().any(|i| i.eq_ignore_ascii_case(id));
+        let allowed = |id: &str| (allow.is_empty() || contains(allow, id)) && !contains(deny, id);
+
+        let mut disallowed = Vec::new();
+        for (license, crates) in &self.licenses {
+            let ok = match license {
+                License::Expression(expression) => spdx::parse(expression)
+                    .is_ok_and(|parsed| parsed.expression.satisfies(&allowed)),
+                License::File | License::None => false,
+            };
+            if !ok {
+                disallowed.extend(crates.iter().map(|c| format!("{c}: {license}")));
+            }
+        }
+        disallowed.extend(
+            self.missing
+                .iter()
+                .map(|(c, reason)| format!("{c}: {reason}")),
+        );
+        disallowed
+    }
+}
+
+/// Cargo names the unpacked sources of a registry after the host of its index, e.g.
+/// `registry.example.com-<hash>` for `sparse+https://registry.example.com/index/`
+fn registry_host(source: &str) -> Option<&str> {
+    let (_, url) = source.split_once("://")?;
+    let authority = url.split('/').next()?;
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    Some(host.split(':').next().unwrap_or(host))
+}
+
+/// The unpacked sources of every registry, e.g. `registry/src/index.crates.io-<hash>`
+fn registries(cargo_home: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(cargo_home.join("registry").join("src")) else {
+        return Vec::new();
+    };
+
+    let mut registries: Vec<_> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    registries.sort();
+    registries
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn registry_host_ok() {
+        assert_eq!(
+            registry_host("sparse+https://registry.example.com/index/"),
+            Some("registry.example.com")
+        );
+        assert_eq!(
+            registry_host("registry+ssh://git@git.example.com:2222/index.git"),
+            Some("git.example.com")
+        );
+        assert_eq!(registry_host("registry+index"), None);
+    }
+}
@@ -0,0 +1,101 @@
+use std::cmp::Reverse;
+
+use cargo_toml::{LintGroups, LintLevel};
+
+fn level_name(level: LintLevel) -> &'static str {
+    match level {
+        LintLevel::Allow => "allow",
+        LintLevel::Warn => "warn",
+        LintLevel::ForceWarn => "force-warn",
+        LintLevel::Deny => "deny",
+        LintLevel::Forbid => "forbid",
+    }
+}
+
+fn level_flag(level: LintLevel) -> &'static str {
+    match level {
+        LintLevel::Allow => "-A",
+        LintLevel::Warn => "-W",
+        LintLevel::ForceWarn => "--force-warn",
+        LintLevel::Deny => "-D",
+        LintLevel::Forbid => "-F",
+    }
+}
+
+/// `tool.lint = level` lines per tool, each tool in the order rustc applies them:
+/// by priority, then by name
+pub fn render(groups: &LintGroups) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for (tool, set) in groups {
+        let mut set: Vec<_> = set.iter().collect();
+        set.sort_by_key(|(name, lint)| (lint.priority, name.as_str()));
+
+        for (name, lint) in set {
+            let level = level_name(lint.level);
+            lines.push(match lint.priority {
+                0 => format!("{tool}.{name} = \"{level}\""),
+                priority => {
+                    format!("{tool}.{name} = {{ level = \"{level}\", priority = {priority} }}")
+                }
+            });
+        }
+    }
+
+    lines
+}
+
+/// Command-line flags in the order Cargo passes them: by priority across all tools, then by
+/// lint name without the tool in reverse. Lints of tools other than `rust` are prefixed with
+/// `tool::`.
+pub fn flags(groups: &LintGroups) -> Vec<String> {
+    let mut lints: Vec<(i8, Reverse<&String>, String)> = groups
+        .iter()
+        .flat_map(|(tool, set)| {
+            set.iter().map(move |(name, lint)| {
+                let flag = match tool.as_str() {
+                    "rust" => format!("{} {name}", level_flag(lint.level)),
+                    tool => format!("{} {tool}::{name}", level_flag(lint.level)),
+                };
+                (lint.priority, Reverse(name), flag)
+            })
+        })
+        .collect();
+    lints.sort();
+
+    lints.into_iter().map(|(_, _, flag)| flag).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn flags_ok() {
+        let manifest = cargo_toml::Manifest::from_str(
+            r#"
+            [workspace.lints.rust]
+            unsafe_code = "forbid"
+            dead_code = "allow"
+            missing_docs = { level = "warn", priority = 1 }
+
+            [workspace.lints.clippy]
+            all = { level = "deny", priority = -1 }
+            unwrap_used = "allow"
+            "#,
+        )
+        .unwrap();
+        let lints = manifest.workspace.unwrap().lints;
+
+        assert_eq!(
+            flags(&lints),
+            vec![
+                "-D clippy::all",
+                "-A clippy::unwrap_used",
+                "-F unsafe_code",
+                "-A dead_code",
+                "-W missing_docs",
+            ]
+        );
+    }
+}
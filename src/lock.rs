@@ -0,0 +1,529 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+    fmt,
+    path::Path,
+};
+
+use cargo_toml::{Dependency, Manifest};
+use toml::Value;
+
+use crate::{json::Json, source::Source, workspace};
+
+/// Where a locked package comes from
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum SourceKind {
+    CratesIo,
+    Registry,
+    Git,
+    Path,
+}
+
+impl fmt::Display for SourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::CratesIo => "crates.io",
+            Self::Registry => "registry",
+            Self::Git => "git",
+            Self::Path => "path",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl SourceKind {
+    pub const ALL: [Self; 4] = [Self::CratesIo, Self::Registry, Self::Git, Self::Path];
+
+    pub fn of(source: Option<&str>) -> Self {
+        match source {
+            None => Self::Path,
+            Some(
+                "registry+https://github.com/rust-lang/crates.io-index"
+                | "sparse+https://index.crates.io/",
+            ) => Self::CratesIo,
+            Some(source) if source.starts_with("git+") => Self::Git,
+            Some(_) => Self::Registry,
+        }
+    }
+}
+
+/// A `[[package]]` entry of `Cargo.lock`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+    pub source: Option<String>,
+    pub checksum: Option<String>,
+    pub dependencies: Vec<String>,
+}
+
+impl Package {
+    pub fn source_kind(&self) -> SourceKind {
+        SourceKind::of(self.source.as_deref())
+    }
+
+    /// Name, version and source, which identify a package even when two sources lock the
+    /// same version of a crate
+    pub fn key(&self) -> (&str, &str, Option<&str>) {
+        (&self.name, &self.version, self.source.as_deref())
+    }
+}
+
+#[derive(Debug)]
+pub struct OutOfSync(pub Vec<String>);
+
+impl fmt::Display for OutOfSync {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cargo.lock is out of sync with the manifests:")?;
+        for issue in &self.0 {
+            write!(f, "\n  {issue}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for OutOfSync {}
+
+#[derive(Debug, PartialEq)]
+pub struct Lockfile {
+    pub version: u32,
+    pub packages: Vec<Package>,
+}
+
+impl Lockfile {
+    pub fn parse(content: &str) -> Result<Self, Box<dyn Error>> {
+        let table: toml::Table = toml::from_str(content)?;
+        let string = |value: &Value, key: &str| value.get(key)?.as_str().map(str::to_owned);
+
+        let packages = table
+            .get("package")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .map(|package| {
+                Ok(Package {
+                    name: string(package, "name").ok_or("Locked package without a name")?,
+                    version: string(package, "version")
+                        .ok_or("Locked package without a version")?,
+                    source: string(package, "source"),
+                    checksum: string(package, "checksum"),
+                    dependencies: package
+                        .get("dependencies")
+                        .and_then(Value::as_array)
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|dep| dep.as_str().map(str::to_owned))
+                        .collect(),
+                })
+            })
+            .collect::<Result<_, Box<dyn Error>>>()?;
+
+        // Only format 3 and later write `version`, format 1 keeps checksums in `[metadata]`
+        let version = match table.get("version").and_then(Value::as_integer) {
+            Some(version) => version as u32,
+            None if table.contains_key("metadata") => 1,
+            None => 2,
+        };
+
+        Ok(Self { version, packages })
+    }
+
+    pub fn load(source: &Source, path: &Path) -> Result<Self, Box<dyn Error>> {
+        if !source.is_file(path) {
+            return Err(format!("No Cargo.lock found at {}", path.display()).into());
+        }
+        let content = source.read_to_string(path)?;
+        Ok(Self::parse(&content)
+            .map_err(|err| format!("Failed to parse {}: {err}", path.display()))?)
+    }
+
+    /// The package a `dependencies` entry refers to: `name`, `name version` or
+    /// `name version (source)`
+    pub fn resolve(&self, dependency: &str) -> Option<&Package> {
+        let mut parts = dependency.splitn(3, ' ');
+        let name = parts.next()?;
+        let version = parts.next();
+        let source = parts
+            .next()
+            .map(|s| s.trim_start_matches('(').trim_end_matches(')'));
+
+        self.packages.iter().find(|p| {
+            p.name == name
+                && version.is_none_or(|v| p.version == v)
+                && source.is_none_or(|s| p.source.as_deref() == Some(s))
+        })
+    }
+
+    /// Requirements no locked version satisfies, members locked at another version than
+    /// their manifest and locked packages no member depends on. Inherited requirements are
+    /// taken from `[workspace.dependencies]` by completing the member manifests.
+    pub fn check(&self, members: &[Manifest]) -> Vec<String> {
+        let mut issues = Vec::new();
+        let mut roots = Vec::new();
+        for manifest in members {
+            let Some(package) = &manifest.package else {
+                continue;
+            };
+            let (name, version) = (package.name(), package.version());
+
+            match self
+                .packages
+                .iter()
+                .find(|p| p.name == name && p.source.is_none())
+            {
+                None => issues.push(format!("{name}: not in Cargo.lock")),
+                Some(locked) => {
+                    if locked.version != version {
+                        issues.push(format!(
+                            "{name}: locked at {} but the manifest has version {version}",
+                            locked.version
+                        ));
+                    }
+                    roots.push(locked);
+                }
+            }
+
+            for (dep_name, dep) in workspace::dependency_sets(manifest).into_iter().flatten() {
+                if let Some(issue) = self.check_requirement(dep_name, dep) {
+                    issues.push(format!("{name}: {issue}"));
+                }
+            }
+        }
+
+        let mut reachable = BTreeSet::new();
+        for root in roots {
+            reachable.insert(root.key());
+            reachable.extend(self.closure(root));
+        }
+        for package in &self.packages {
+            if !reachable.contains(&package.key()) {
+                issues.push(format!("orphaned: {} {}", package.name, package.version));
+            }
+        }
+
+        issues
+    }
+
+    fn check_requirement(&self, name: &str, dep: &Dependency) -> Option<String> {
+        let req = dep.try_req().ok()?;
+        let locked: Vec<_> = self
+            .packages
+            .iter()
+            .filter(|p| p.name == dep.package().unwrap_or(name))
+            .collect();
+
+        if locked.is_empty() {
+            return Some(format!("{name} = \"{req}\" is not in Cargo.lock"));
+        }
+
+        let Ok(parsed) = req.parse::<semver::VersionReq>() else {
+            return Some(format!(
+                "{name} = \"{req}\" is not a valid version requirement"
+            ));
+        };
+        let satisfied = locked.iter().any(|p| {
+            p.version
+                .parse()
+                .is_ok_and(|version| parsed.matches(&version))
+        });
+
+        match satisfied {
+            true => None,
+            false => {
+                let versions: Vec<_> = locked.iter().map(|p| p.version.as_str()).collect();
+                Some(format!(
+                    "{name} = \"{req}\" is not satisfied by locked {}",
+                    versions.join(", ")
+                ))
+            }
+        }
+    }
+
+    /// All packages `package` depends on, directly or transitively
+    pub fn closure<'a>(
+        &'a self,
+        package: &'a Package,
+    ) -> BTreeSet<(&'a str, &'a str, Option<&'a str>)> {
+        let mut seen = BTreeSet::new();
+        let mut stack = vec![package];
+        while let Some(package) = stack.pop() {
+            for dep in package.dependencies.iter().filter_map(|d| self.resolve(d)) {
+                if seen.insert(dep.key()) {
+                    stack.push(dep);
+                }
+            }
+        }
+        seen
+    }
+
+    pub fn summary(&self, members: &[String]) -> Summary {
+        let mut sources: BTreeMap<SourceKind, usize> =
+            SourceKind::ALL.iter().map(|kind| (*kind, 0)).collect();
+        for package in &self.packages {
+            *sources.entry(package.source_kind()).or_default() += 1;
+        }
+
+        let closures: Vec<_> = members
+            .iter()
+            .filter_map(|member| {
+                self.packages
+                    .iter()
+                    .find(|p| &p.name == member && p.source.is_none())
+            })
+            .map(|member| (member.name.as_str(), self.closure(member)))
+            .collect();
+
+        let mut by_name: BTreeMap<&str, BTreeMap<Compat, Vec<&Package>>> = BTreeMap::new();
+        for package in &self.packages {
+            if let Ok(version) = package.version.parse() {
+                by_name
+                    .entry(&package.name)
+                    .or_default()
+                    .entry(compat(&version))
+                    .or_default()
+                    .push(package);
+            }
+        }
+
+        let duplicates = by_name
+            .into_iter()
+            .filter(|(_, groups)| groups.len() > 1)
+            .map(|(name, groups)| Duplicate {
+                name: name.to_owned(),
+                versions: groups
+                    .into_values()
+                    .flatten()
+                    .map(|package| {
+                        let users = closures
+                            .iter()
+                            .filter(|(_, closure)| closure.contains(&package.key()))
+                            .map(|(member, _)| member.to_string())
+                            .collect();
+                        (package.version.clone(), users)
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Summary {
+            version: self.version,
+            packages: self.packages.len(),
+            sources,
+            duplicates,
+        }
+    }
+}
+
+/// Versions that can be unified by Cargo share the same left-most non-zero component
+type Compat = (u64, u64, u64);
+
+fn compat(version: &semver::Version) -> Compat {
+    match (version.major, version.minor) {
+        (0, 0) => (0, 0, version.patch),
+        (0, minor) => (0, minor, 0),
+        (major, _) => (major, 0, 0),
+    }
+}
+
+/// A crate locked in several semver incompatible versions
+#[derive(Debug, PartialEq)]
+pub struct Duplicate {
+    pub name: String,
+    /// Each version with the workspace members that pull it in
+    pub versions: Vec<(String, Vec<String>)>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Summary {
+    pub version: u32,
+    pub packages: usize,
+    pub sources: BTreeMap<SourceKind, usize>,
+    pub duplicates: Vec<Duplicate>,
+}
+
+impl Summary {
+    pub fn lines(&self) -> Vec<String> {
+        let sources = self
+            .sources
+            .iter()
+            .map(|(kind, count)| format!("{kind} {count}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut lines = vec![
+            format!("version: {}", self.version),
+            format!("packages: {}", self.packages),
+            format!("sources: {sources}"),
+        ];
+
+        for duplicate in &self.duplicates {
+            let versions = duplicate
+                .versions
+                .iter()
+                .map(|(version, users)| match users.is_empty() {
+                    true => version.clone(),
+                    false => format!("{version} ({})", users.join(", ")),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            lines.push(format!("duplicate: {} {versions}", duplicate.name));
+        }
+
+        lines
+    }
+
+    pub fn to_json(&self) -> Json {
+        Json::object(vec![
+            ("version", Json::from(self.version as usize)),
+            ("packages", self.packages.into()),
+            (
+                "sources",
+                Json::object(
+                    self.sources
+                        .iter()
+                        .map(|(kind, count)| (kind.to_string(), Json::from(*count))),
+                ),
+            ),
+            (
+                "duplicates",
+                self.duplicates
+                    .iter()
+                    .map(|duplicate| {
+                        let versions = duplicate.versions.iter().map(|(version, users)| {
+                            Json::object(vec![
+                                ("version", Json::from(version.as_str())),
+                                ("members", users.iter().map(String::as_str).collect()),
+                            ])
+                        });
+                        Json::object(vec![
+                            ("name", Json::from(duplicate.name.as_str())),
+                            ("versions", versions.collect()),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn resolve_ok() {
+        let lock = Lockfile::parse(
+            r#"
+            version = 4
+
+            [[package]]
+            name = "app"
+            version = "0.1.0"
+            dependencies = ["syn 1.0.109", "syn 2.0.90 (registry+https://github.com/rust-lang/crates.io-index)"]
+
+            [[package]]
+            name = "syn"
+            version = "1.0.109"
+            source = "registry+https://github.com/rust-lang/crates.io-index"
+
+            [[package]]
+            name = "syn"
+            version = "2.0.90"
+            source = "registry+https://github.com/rust-lang/crates.io-index"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(lock.version, 4);
+        assert!(lock.resolve("syn").is_some());
+        assert_eq!(lock.resolve("syn 2.0.90").unwrap().version, "2.0.90");
+        assert!(lock.resolve("syn 3.0.0").is_none());
+
+        let summary = lock.summary(&["app".to_owned()]);
+        assert_eq!(summary.sources[&SourceKind::CratesIo], 2);
+        assert_eq!(summary.duplicates[0].name, "syn");
+        assert_eq!(summary.duplicates[0].versions.len(), 2);
+    }
+
+    #[test]
+    fn resolve_same_version_from_two_sources() {
+        let lock = Lockfile::parse(
+            r#"
+            version = 4
+
+            [[package]]
+            name = "app"
+            version = "0.1.0"
+            dependencies = [
+             "log 0.4.20 (registry+https://github.com/rust-lang/crates.io-index)",
+             "log 0.4.20 (registry+https://registry.example.com/index)",
+            ]
+
+            [[package]]
+            name = "log"
+            version = "0.4.20"
+            source = "registry+https://github.com/rust-lang/crates.io-index"
+
+            [[package]]
+            name = "log"
+            version = "0.4.20"
+            source = "registry+https://registry.example.com/index"
+            "#,
+        )
+        .unwrap();
+
+        let mirror = lock
+            .resolve("log 0.4.20 (registry+https://registry.example.com/index)")
+            .unwrap();
+        assert_eq!(mirror, &lock.packages[2]);
+        assert_eq!(lock.closure(&lock.packages[0]).len(), 2);
+    }
+
+    #[test]
+    fn summary_members_are_path_packages() {
+        let lock = Lockfile::parse(
+            r#"
+            version = 4
+
+            [[package]]
+            name = "app"
+            version = "9.0.0"
+            source = "registry+https://github.com/rust-lang/crates.io-index"
+            dependencies = ["syn 2.0.90"]
+
+            [[package]]
+            name = "app"
+            version = "0.1.0"
+            dependencies = ["syn 1.0.109"]
+
+            [[package]]
+            name = "syn"
+            version = "1.0.109"
+            source = "registry+https://github.com/rust-lang/crates.io-index"
+
+            [[package]]
+            name = "syn"
+            version = "2.0.90"
+            source = "registry+https://github.com/rust-lang/crates.io-index"
+            "#,
+        )
+        .unwrap();
+
+        let summary = lock.summary(&["app".to_owned()]);
+        assert_eq!(
+            summary.duplicates[1].versions,
+            vec![
+                ("1.0.109".to_owned(), vec!["app".to_owned()]),
+                ("2.0.90".to_owned(), vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn compat_ok() {
+        let compat = |v: &str| compat(&v.parse().unwrap());
+        assert_eq!(compat("1.2.3"), compat("1.9.0"));
+        assert_ne!(compat("0.1.0"), compat("0.2.0"));
+        assert_ne!(compat("0.0.1"), compat("0.0.2"));
+    }
+}
@@ -1,14 +1,40 @@
+mod artifacts;
+mod authors;
+mod cfg;
+mod changelog;
 mod cli;
+mod config;
+mod context;
+mod debian;
 mod delimiter;
+mod diff;
 mod error;
+mod git;
+mod graph;
+mod json;
+mod licenses;
+mod lints;
+mod lock;
+mod oci;
 mod output_format;
+mod packaging;
+mod sbom;
+mod source;
+mod spdx;
+mod tag;
+mod targets;
 mod terminator;
+mod urls;
+mod version_sync;
+mod workspace;
 
-use cargo_toml::Manifest;
+use cargo_toml::Inheritable;
 use clap::Parser;
 use cli::MaybeCommand;
+use context::Context;
 use delimiter::Delimiter;
 use error::NotSpecified;
+use source::Source;
 use std::{error::Error, path::PathBuf};
 use terminator::Terminator;
 
@@ -42,197 +68,81 @@ pub fn output(cli: cli::Cli) -> Result<String, Box<dyn Error>> {
     let entry_point_absolute =
         std::fs::canonicalize(entry_point).map_err(|_| "No such file or directory")?;
 
-    let manifest_path = search_manifest_path(&entry_point_absolute).ok_or("No manifest found")?;
+    if let MaybeCommand::Config(args) = &cli.command {
+        let dir = match entry_point_absolute.is_file() {
+            true => entry_point_absolute
+                .parent()
+                .unwrap_or(&entry_point_absolute),
+            false => &entry_point_absolute,
+        };
 
-    let manifest = Manifest::from_path(manifest_path)?;
+        let root = config::load(dir)?;
+        let node = config::get(&root, &args.key)?;
+        let delimiter: Delimiter = cli.delimiter.unwrap_or_default();
+        let terminator: Terminator = cli.terminator.unwrap_or_default();
 
-    let package = || manifest.package.clone().ok_or(NotSpecified("package"));
-    let workspace = || manifest.workspace.clone().ok_or(NotSpecified("workspace"));
-    let ws_package =
-        || workspace().and_then(|ws| ws.package.ok_or(NotSpecified("workspace.package")));
+        let mut output = node.render(args.show_origin).join(&delimiter.to_string());
+        output.push_str(terminator.to_string().as_ref());
+        return Ok(output);
+    }
 
-    let delimiter: Delimiter = cli.delimiter.unwrap_or_default();
-    let delim_string = delimiter.to_string();
-    let terminator: Terminator = cli.terminator.unwrap_or_default();
+    if let MaybeCommand::Diff(args) = &cli.command {
+        let a = diff::open(&args.a, &entry_point_absolute)?;
+        let b = diff::open(&args.b, &entry_point_absolute)?;
+        let changes = diff::changes(&a, &b);
+
+        let unexpected: Vec<_> = changes
+            .iter()
+            .filter(|change| args.fail_on.iter().any(|rule| change.matches(rule)))
+            .map(ToString::to_string)
+            .collect();
+        if !unexpected.is_empty() {
+            return Err(diff::UnexpectedChanges(unexpected).into());
+        }
 
-    let get_output = |cmd: &cli::Command| {
-        let output = match cmd {
-            cli::Command::PackageVersion { inner } => {
-                let v: semver::Version = package()?.version().parse()?;
-                inner.match_version(v, &delimiter)?
-            }
-            cli::Command::PackageAuthors => package()?.authors().join(&delim_string),
-
-            cli::Command::PackageEdition => package()?.edition().to_string(),
-            cli::Command::PackageName => package()?.name().to_string(),
-            cli::Command::PackageHomepage => package()?
-                .homepage()
-                .ok_or(NotSpecified("package.homepage"))?
-                .to_string(),
-            cli::Command::PackageKeywords => package()?.keywords().join(&delim_string),
-            cli::Command::PackageLicense => package()?
-                .license()
-                .ok_or(NotSpecified("package.license"))?
-                .to_string(),
-            cli::Command::PackageLinks => package()?
-                .links()
-                .ok_or(NotSpecified("package.links"))?
-                .to_string(),
-            cli::Command::PackageDescription => package()?
-                .description()
-                .ok_or(NotSpecified("package.description"))?
-                .to_string(),
-            cli::Command::PackageCategories => package()?.categories().join(&delim_string),
-            cli::Command::PackageRustVersion => package()?
-                .rust_version()
-                .ok_or(NotSpecified("package.rust_version"))?
-                .to_string(),
-            cli::Command::PackageBuild => package()?
-                .build
-                .ok_or(NotSpecified("package.build"))?
-                .as_path()
-                .unwrap()
-                .to_string_lossy()
-                .to_string(),
-
-            cli::Command::PackageWorkspace => package()?
-                .workspace
-                .ok_or(NotSpecified("package.workspace"))?
-                .to_string_lossy()
-                .to_string(),
-
-            cli::Command::PackageReadme => package()?
-                .readme()
-                .as_path()
-                .ok_or(NotSpecified("package.readme"))?
-                .to_string_lossy()
-                .to_string(),
-
-            cli::Command::PackageExclude => package()?.exclude().join(&delim_string),
-            cli::Command::PackageInclude => package()?.include().join(&delim_string),
-            cli::Command::PackageLicenseFile => package()?
-                .license_file()
-                .ok_or(NotSpecified("package.license_file"))?
-                .to_string_lossy()
-                .to_string(),
-
-            cli::Command::PackageRepository => package()?
-                .repository()
-                .ok_or(NotSpecified("package.repository"))?
-                .to_string(),
-
-            cli::Command::PackageDefaultRun => package()?
-                .default_run
-                .ok_or(NotSpecified("package.default_run"))?
-                .to_string(),
-
-            cli::Command::PackagePublish => match package()?.publish() {
-                cargo_toml::Publish::Flag(flag) => flag.to_string(),
-                cargo_toml::Publish::Registry(list) => list.join(&delim_string),
-            },
-            cli::Command::PackageResolver => package()?
-                .resolver
-                .ok_or(NotSpecified("package.resolver"))?
-                .to_string(),
-
-            cli::Command::PackageMetadata => package()?
-                .metadata
-                .ok_or(NotSpecified("package.metadata"))?
-                .to_string(),
-
-            cli::Command::WorkspaceMembers => workspace()?.members.join(&delim_string),
-
-            cli::Command::WorkspaceDefaultMembers => {
-                workspace()?.default_members.join(&delim_string)
-            }
+        let delimiter: Delimiter = cli.delimiter.unwrap_or_default();
+        let terminator: Terminator = cli.terminator.unwrap_or_default();
+
+        let mut output = changes
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(&delimiter.to_string());
+        output.push_str(terminator.to_string().as_ref());
+        return Ok(output);
+    }
 
-            cli::Command::WorkspacePackageVersion { inner } => {
-                let v: semver::Version = ws_package()?
-                    .version
-                    .ok_or(NotSpecified("workspace.package.version"))?
-                    .parse()?;
-                inner.match_version(v, &delimiter)?
+    let source = match &cli.rev {
+        Some(rev) => {
+            let dir = match entry_point_absolute.is_file() {
+                true => entry_point_absolute
+                    .parent()
+                    .unwrap_or(&entry_point_absolute),
+                false => &entry_point_absolute,
+            };
+            let revision = git::Revision::open(dir, rev)?;
+            if !revision.is_file(&entry_point_absolute) && !revision.is_dir(&entry_point_absolute) {
+                return Err(revision.not_found(&entry_point_absolute).into());
             }
+            Source::Git(revision)
+        }
+        None => Source::WorkingTree,
+    };
 
-            cli::Command::WorkspacePackageAuthors => ws_package()?
-                .authors
-                .ok_or(NotSpecified("workspace.package.authors"))?
-                .join(&delim_string),
-
-            cli::Command::WorkspacePackageEdition => ws_package()?
-                .edition
-                .map(|edition| edition.to_string())
-                .ok_or(NotSpecified("workspace.package.edition"))?
-                .to_string(),
-
-            cli::Command::WorkspacePackageHomepage => ws_package()?
-                .homepage
-                .ok_or(NotSpecified("workspace.package.homepage"))?,
-
-            cli::Command::WorkspacePackageKeywords => ws_package()?
-                .keywords
-                .ok_or(NotSpecified("workspace.package.keywords"))?
-                .join(&delim_string),
-
-            cli::Command::WorkspacePackageLicense => ws_package()?
-                .license
-                .ok_or(NotSpecified("workspace.package.license"))?,
-
-            cli::Command::WorkspacePackageDescription => ws_package()?
-                .description
-                .ok_or(NotSpecified("workspace.package.license"))?,
-
-            cli::Command::WorkspacePackageCategories => ws_package()?
-                .categories
-                .ok_or(NotSpecified("workspace.package.categories"))?
-                .join(&delim_string),
-            cli::Command::WorkspacePackageDocumentation => ws_package()?
-                .documentation
-                .ok_or(NotSpecified("workspace.package.documentation"))?,
-
-            cli::Command::WorkspacePackageExclude => ws_package()?
-                .exclude
-                .ok_or(NotSpecified("workspace.package.exclude"))?
-                .join(&delim_string),
-
-            cli::Command::WorkspacePackageInclude => ws_package()?
-                .include
-                .ok_or(NotSpecified("workspace.package.include"))?
-                .join(&delim_string),
-
-            cli::Command::WorkspacePackageLicenseFile => ws_package()?
-                .license_file
-                .ok_or(NotSpecified("workspace.package.license_file"))?
-                .to_string_lossy()
-                .to_string(),
-
-            cli::Command::WorkspacePackagePublish => match ws_package()?.publish {
-                cargo_toml::Publish::Flag(flag) => flag.to_string(),
-                cargo_toml::Publish::Registry(list) => list.join(&delim_string),
-            },
-            cli::Command::WorkspacePackageReadme => ws_package()?
-                .readme
-                .as_path()
-                .ok_or(NotSpecified("workspace.package.readme"))?
-                .to_string_lossy()
-                .to_string(),
-
-            cli::Command::WorkspacePackageRepository => ws_package()?
-                .repository
-                .ok_or(NotSpecified("workspace.package.repository"))?,
-
-            cli::Command::WorkspacePackageRustVersion => ws_package()?
-                .rust_version
-                .ok_or(NotSpecified("workspace.package.rust_version"))?
-                .to_string(),
-        };
+    let ctx = Context::load(source, &entry_point_absolute)?;
 
-        Result::<_, Box<dyn Error>>::Ok(output)
-    };
+    let delimiter: Delimiter = cli.delimiter.unwrap_or_default();
+    let delim_string = delimiter.to_string();
+    let terminator: Terminator = cli.terminator.unwrap_or_default();
 
     let output = match cli.command {
         MaybeCommand::Command(cmd) => {
-            let mut output = get_output(&cmd)?;
+            let mut output = ctx.get(&cmd, &delimiter)?;
+            output.push_str(terminator.to_string().as_ref());
+            output
+        }
+        MaybeCommand::Derived(cmd) => {
+            let mut output = ctx.derived(&cmd)?;
             output.push_str(terminator.to_string().as_ref());
             output
         }
@@ -241,24 +151,254 @@ pub fn output(cli: cli::Cli) -> Result<String, Box<dyn Error>> {
 
             cli::Command::iter()
                 .filter_map(|cmd| {
-                    let output = get_output(&cmd).ok()?;
+                    let output = ctx.get(&cmd, &delimiter).ok()?;
                     Some((cmd, output))
                 })
                 .map(|(cmd, res)| all.output_format.format_pair(cmd, &res))
                 .collect()
         }
-    };
+        MaybeCommand::Artifacts(args) => {
+            let files = artifacts::file_names(&ctx.manifest, &args.target);
+            let target_dir = match (&args.target_dir, args.paths) {
+                (Some(dir), _) => dir.clone(),
+                (None, true) => {
+                    let root = ctx.workspace_root().unwrap_or(ctx.manifest_path.clone());
+                    artifacts::target_dir(root.parent().ok_or("Invalid manifest path")?)?
+                }
+                (None, false) => PathBuf::new(),
+            };
+            let dir = target_dir
+                .join(args.target.triple)
+                .join(artifacts::profile_dir(&args.profile));
+
+            let mut output = files
+                .iter()
+                .map(|file| match args.paths {
+                    true => dir.join(file).to_string_lossy().to_string(),
+                    false => file.clone(),
+                })
+                .collect::<Vec<_>>()
+                .join(&delim_string);
+            output.push_str(terminator.to_string().as_ref());
+            output
+        }
+        MaybeCommand::Dependencies(args) => {
+            let mut dependencies: Vec<_> = ctx.manifest.dependencies.iter().collect();
+
+            for (platform, target) in &ctx.manifest.target {
+                let applies = match &args.target {
+                    Some(triple) => {
+                        let platform: cfg::Platform = platform.parse()?;
+                        let mut cfgs = cfg::target_cfgs(triple);
+                        cfgs.extend(args.cfg.iter().cloned());
+                        platform.matches(triple, &cfgs)
+                    }
+                    None => true,
+                };
+                if applies {
+                    dependencies.extend(&target.dependencies);
+                }
+            }
 
-    Ok(output)
-}
+            let mut lines: Vec<_> = dependencies
+                .into_iter()
+                .map(|(name, dep)| workspace::describe_dependency(name, dep))
+                .collect();
+            lines.sort();
+            lines.dedup();
 
-/// Search the given directory for Cargo.toml, recursively searching upwards
-fn search_manifest_path(dir: &std::path::Path) -> Option<PathBuf> {
-    let manifest = dir.join("Cargo.toml");
+            let mut output = lines.join(&delim_string);
+            output.push_str(terminator.to_string().as_ref());
+            output
+        }
+        MaybeCommand::WorkspaceGraph(args) => {
+            let root_manifest = ctx.workspace_root()?;
+            let root = ctx.source.manifest(&root_manifest)?;
+            let graph = graph::Graph::load(&ctx.source, &root_manifest, &root)?;
 
-    if std::fs::metadata(&manifest).is_ok() {
-        Some(manifest)
-    } else {
-        dir.parent().and_then(search_manifest_path)
-    }
+            let mut output = args.match_graph(&graph, &delimiter)?;
+            output.push_str(terminator.to_string().as_ref());
+            output
+        }
+        MaybeCommand::WorkspaceAffected(args) => {
+            let input = match &args.file {
+                Some(file) => std::fs::read_to_string(file)
+                    .map_err(|err| format!("Failed to read {}: {err}", file.display()))?,
+                None => std::io::read_to_string(std::io::stdin())?,
+            };
+            let changed: Vec<PathBuf> = input
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(PathBuf::from)
+                .collect();
+
+            let root_manifest = ctx.workspace_root()?;
+            let root = ctx.source.manifest(&root_manifest)?;
+            let graph = graph::Graph::load(&ctx.source, &root_manifest, &root)?;
+
+            let base = match &args.base_dir {
+                Some(dir) => std::fs::canonicalize(dir)
+                    .map_err(|err| format!("Failed to read {}: {err}", dir.display()))?,
+                None => git::toplevel(&graph.root_dir).unwrap_or(graph.root_dir.clone()),
+            };
+
+            let mut output = args.match_affected(&graph, &base, &changed, &delimiter);
+            output.push_str(terminator.to_string().as_ref());
+            output
+        }
+        MaybeCommand::Lints(args) => {
+            let mut lints = match &ctx.manifest.lints {
+                Inheritable::Set(lints) => lints.clone(),
+                Inheritable::Inherited => ctx.workspace()?.lints,
+            };
+            if let Some(tool) = &args.tool {
+                lints.retain(|name, _| name == tool);
+            }
+            if lints.values().all(|set| set.is_empty()) {
+                return Err(NotSpecified("lints").into());
+            }
+
+            let mut output = match args.as_flags {
+                true => lints::flags(&lints),
+                false => lints::render(&lints),
+            }
+            .join(&delim_string);
+            output.push_str(terminator.to_string().as_ref());
+            output
+        }
+        MaybeCommand::LockSummary(args) => {
+            let lockfile = lock::Lockfile::load(&ctx.source, &ctx.lockfile_path()?)?;
+            let summary = lockfile.summary(&ctx.member_names()?);
+
+            let mut output = match args.json {
+                true => summary.to_json().to_string(),
+                false => summary.lines().join(&delim_string),
+            };
+            output.push_str(terminator.to_string().as_ref());
+            output
+        }
+        MaybeCommand::Check(args) => match args.check {
+            cli::CheckCommand::Lock => {
+                let lockfile = lock::Lockfile::load(&ctx.source, &ctx.lockfile_path()?)?;
+                let issues = lockfile.check(&ctx.member_manifests()?);
+                if !issues.is_empty() {
+                    return Err(lock::OutOfSync(issues).into());
+                }
+                String::new()
+            }
+            cli::CheckCommand::VersionSync { files, fix } => {
+                let version: semver::Version = ctx
+                    .package()?
+                    .version()
+                    .parse()
+                    .map_err(error::InvalidSemver)?;
+                let fixed = version_sync::sync(&files, &version, fix)?;
+                if fixed.is_empty() {
+                    return Ok(String::new());
+                }
+
+                let mut output = fixed.join(&delim_string);
+                output.push_str(terminator.to_string().as_ref());
+                output
+            }
+            cli::CheckCommand::ReleaseTag { tag, pattern } => {
+                let tag = match tag {
+                    Some(tag) => tag,
+                    None => tag::from_env()?,
+                };
+                let packages = match pattern.pattern().contains("{name}") {
+                    true => ctx.member_manifests()?,
+                    false => {
+                        ctx.package()?;
+                        vec![ctx.manifest.clone()]
+                    }
+                };
+
+                let mut output = tag::check(&tag, pattern.pattern(), &packages)?;
+                output.push_str(terminator.to_string().as_ref());
+                output
+            }
+        },
+        MaybeCommand::Sbom(args) => {
+            let lockfile = lock::Lockfile::load(&ctx.source, &ctx.lockfile_path()?)?;
+            let sbom = sbom::Sbom::new(&ctx.manifest, &lockfile)?;
+            let mut output = sbom.render(&args.format, sbom::creation_time()?);
+            output.push_str(terminator.to_string().as_ref());
+            output
+        }
+        MaybeCommand::Licenses(args) => {
+            let lockfile = lock::Lockfile::load(&ctx.source, &ctx.lockfile_path()?)?;
+            let cargo_home = config::cargo_home().ok_or("Unable to locate CARGO_HOME")?;
+            let report = licenses::Report::new(&lockfile, &cargo_home)?;
+
+            if !args.allow.is_empty() || !args.deny.is_empty() {
+                let disallowed = report.disallowed(&args.allow, &args.deny);
+                if !disallowed.is_empty() {
+                    return Err(licenses::Disallowed(disallowed).into());
+                }
+            }
+
+            let mut output = report.lines().join(&delim_string);
+            output.push_str(terminator.to_string().as_ref());
+            output
+        }
+        MaybeCommand::Debian(args) => {
+            let debian = debian::Debian::new(&ctx)?;
+            let mut output = match args.file {
+                cli::DebianFile::Control => debian.control(),
+                cli::DebianFile::Copyright => debian.copyright()?,
+            };
+            output.push_str(terminator.to_string().as_ref());
+            output
+        }
+        MaybeCommand::Packaging(args) => {
+            let packaging = packaging::Packaging::new(&ctx, &args.tag)?;
+            let mut output = packaging.render(&args.format)?;
+            output.push_str(terminator.to_string().as_ref());
+            output
+        }
+        MaybeCommand::OciLabels(args) => {
+            let labels = oci::labels(&ctx, args.revision.as_deref())?;
+            let mut output = oci::render(&labels, &args.format, &delim_string);
+            output.push_str(terminator.to_string().as_ref());
+            output
+        }
+        MaybeCommand::Changelog(args) => {
+            let (content, file) = match &args.file {
+                Some(file) => (
+                    std::fs::read_to_string(file)
+                        .map_err(|err| format!("Failed to read {}: {err}", file.display()))?,
+                    file.display().to_string(),
+                ),
+                None => {
+                    let path = ctx.manifest_path.with_file_name("CHANGELOG.md");
+                    if !ctx.source.is_file(&path) {
+                        return Err("No CHANGELOG.md next to the manifest".into());
+                    }
+                    (ctx.source.read_to_string(&path)?, "CHANGELOG.md".to_owned())
+                }
+            };
+            let version = match args.version {
+                Some(version) => version,
+                None => ctx.package()?.version().to_owned(),
+            };
+
+            let sections = changelog::parse(&content);
+            if args.check {
+                changelog::check(&sections, &version, &file)?;
+            }
+            let section = changelog::find(&sections, &version)
+                .ok_or_else(|| changelog::no_section(&version, &file))?;
+
+            let mut output = section.body.clone();
+            output.push_str(terminator.to_string().as_ref());
+            output
+        }
+        MaybeCommand::Config(_) | MaybeCommand::Diff(_) => {
+            unreachable!("handled before loading the manifest")
+        }
+    };
+
+    Ok(output)
 }
@@ -0,0 +1,105 @@
+use std::error::Error;
+
+use clap::ValueEnum;
+
+use crate::{context::Context, json::Json, packaging, spdx, urls};
+
+#[derive(Debug, PartialEq, Clone, Default, ValueEnum)]
+pub enum OciFormat {
+    /// `--label key=value` arguments for `docker build` and `buildah`
+    #[default]
+    Args,
+    /// A `LABEL` instruction
+    Dockerfile,
+    Json,
+}
+
+/// The `org.opencontainers.image.*` annotations that can be derived from the package
+pub fn labels(
+    ctx: &Context,
+    revision: Option<&str>,
+) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    let package = ctx.package()?;
+
+    let license = package.license().map(spdx::normalize_or_raw);
+    let documentation = match package.documentation() {
+        Some(docs) => docs.to_owned(),
+        None => urls::docs_rs(package.name(), package.version()),
+    };
+    let authors = Some(package.authors().join(", ")).filter(|a| !a.is_empty());
+
+    let labels = vec![
+        ("title", Some(package.name().to_owned())),
+        ("description", package.description().map(str::to_owned)),
+        ("version", Some(package.version().to_owned())),
+        ("licenses", license),
+        ("source", package.repository().map(str::to_owned)),
+        (
+            "url",
+            package
+                .homepage()
+                .or_else(|| package.repository())
+                .map(str::to_owned),
+        ),
+        ("documentation", Some(documentation)),
+        ("authors", authors),
+        ("revision", revision.map(str::to_owned)),
+    ];
+
+    Ok(labels
+        .into_iter()
+        .filter_map(|(key, value)| Some((format!("org.opencontainers.image.{key}"), value?)))
+        .collect())
+}
+
+pub fn render(labels: &[(String, String)], format: &OciFormat, delimiter: &str) -> String {
+    match format {
+        OciFormat::Args => labels
+            .iter()
+            .map(|(key, value)| format!("--label {}", packaging::quote(&format!("{key}={value}"))))
+            .collect::<Vec<_>>()
+            .join(delimiter),
+        OciFormat::Dockerfile => {
+            let labels: Vec<_> = labels
+                .iter()
+                .map(|(key, value)| format!("{key}={}", dockerfile_string(value)))
+                .collect();
+            format!("LABEL {}", labels.join(" \\\n      "))
+        }
+        OciFormat::Json => Json::object(
+            labels
+                .iter()
+                .map(|(key, value)| (key.as_str(), Json::from(value.as_str()))),
+        )
+        .to_string(),
+    }
+}
+
+/// Double quoted, with the characters the Dockerfile parser interprets escaped. A newline
+/// would end the instruction, so lines are folded into one.
+fn dockerfile_string(value: &str) -> String {
+    let folded = value
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let escaped = folded
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('$', "\\$");
+    format!("\"{escaped}\"")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dockerfile_string_multi_line() {
+        assert_eq!(
+            dockerfile_string("Ship logs\nto a \"remote\" collector.\r\n\n  Costs $0.\n"),
+            r#""Ship logs to a \"remote\" collector. Costs \$0.""#
+        );
+    }
+}
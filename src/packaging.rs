@@ -0,0 +1,596 @@
+use std::error::Error;
+
+use cargo_toml::Value;
+use clap::ValueEnum;
+
+use crate::{
+    cli::Tag,
+    context::Context,
+    error::NotSpecified,
+    spdx::{self, Expression},
+    urls::Repository,
+    workspace,
+};
+
+#[derive(Debug, PartialEq, Clone, ValueEnum)]
+pub enum PackagingFormat {
+    RpmSpec,
+    Pkgbuild,
+    Homebrew,
+    Nix,
+}
+
+/// Checksums are left for the user to fill in, `nix` reports the real hash on the first build
+const SHA256_PLACEHOLDER: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// A file installed by the package
+#[derive(Debug, PartialEq, Clone)]
+pub struct Asset {
+    pub source: String,
+    pub dest: String,
+    pub mode: String,
+}
+
+/// The package fields distribution packages are generated from
+#[derive(Debug, PartialEq)]
+pub struct Packaging {
+    pub name: String,
+    pub version: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub license: Option<String>,
+    pub bins: Vec<String>,
+    /// `default-run` or the only bin target
+    pub main_program: Option<String>,
+    pub repository: Option<String>,
+    /// The release tag of this version
+    pub tag: String,
+    /// `[package.metadata.generate-rpm]`
+    pub rpm: Option<Value>,
+}
+
+impl Packaging {
+    pub fn new(ctx: &Context, tag: &Tag) -> Result<Self, Box<dyn Error>> {
+        let package = ctx.package()?;
+        let bins: Vec<_> = ctx
+            .manifest
+            .bin
+            .iter()
+            .filter_map(|bin| bin.name.clone())
+            .collect();
+        let main_program = match (&package.default_run, bins.as_slice()) {
+            (Some(default_run), _) => Some(default_run.clone()),
+            (None, [bin]) => Some(bin.clone()),
+            _ => None,
+        };
+
+        Ok(Self {
+            name: package.name().to_owned(),
+            version: package.version().to_owned(),
+            description: package.description().map(str::to_owned),
+            url: package
+                .homepage()
+                .or_else(|| package.repository())
+                .map(str::to_owned),
+            license: package.license().map(spdx::normalize_or_raw),
+            bins,
+            main_program,
+            repository: package.repository().map(str::to_owned),
+            tag: tag.render(package.name(), package.version()),
+            rpm: package
+                .metadata
+                .as_ref()
+                .and_then(|metadata| workspace::lookup(metadata, "generate-rpm"))
+                .cloned(),
+        })
+    }
+
+    pub fn render(&self, format: &PackagingFormat) -> Result<String, Box<dyn Error>> {
+        match format {
+            PackagingFormat::RpmSpec => self.rpm_spec(),
+            PackagingFormat::Pkgbuild => self.pkgbuild(),
+            PackagingFormat::Homebrew => self.homebrew(),
+            PackagingFormat::Nix => self.nix(),
+        }
+    }
+
+    /// The tarball of the release tag, shared by the Homebrew formula and the Nix derivation
+    fn archive_url(&self) -> Result<String, Box<dyn Error>> {
+        let repository = self
+            .repository
+            .as_deref()
+            .ok_or(NotSpecified("package.repository"))?;
+        Ok(Repository::parse(repository)?.archive_url(&self.tag)?)
+    }
+
+    fn license_expression(&self) -> Result<Expression, Box<dyn Error>> {
+        let license = self
+            .license
+            .as_deref()
+            .ok_or(NotSpecified("package.license"))?;
+        Ok(spdx::parse(license)?.expression)
+    }
+
+    fn rpm_field(&self, key: &str) -> Option<String> {
+        let value = workspace::lookup(self.rpm.as_ref()?, key)?;
+        match value {
+            Value::String(s) => Some(s.clone()),
+            value => Some(value.to_string()),
+        }
+    }
+
+    /// `[[package.metadata.generate-rpm.assets]]` or the release binaries
+    fn rpm_assets(&self) -> Vec<Asset> {
+        let assets = self
+            .rpm
+            .as_ref()
+            .and_then(|rpm| workspace::lookup(rpm, "assets"))
+            .and_then(Value::as_array);
+
+        let Some(assets) = assets else {
+            return self
+                .bins
+                .iter()
+                .map(|bin| Asset {
+                    source: format!("target/release/{bin}"),
+                    dest: format!("%{{_bindir}}/{bin}"),
+                    mode: "755".to_owned(),
+                })
+                .collect();
+        };
+
+        assets
+            .iter()
+            .filter_map(|asset| {
+                let field = |key| workspace::lookup(asset, key)?.as_str();
+                let source = field("source")?;
+                let dest = match field("dest")? {
+                    dir if dir.ends_with('/') => {
+                        format!("{dir}{}", source.rsplit('/').next().unwrap_or(source))
+                    }
+                    dest => dest.to_owned(),
+                };
+                Some(Asset {
+                    source: source.to_owned(),
+                    dest,
+                    mode: field("mode").unwrap_or("644").to_owned(),
+                })
+            })
+            .collect()
+    }
+
+    /// Fedora uses SPDX expressions for `License:`, so the normalized expression is used as is
+    fn rpm_spec(&self) -> Result<String, Box<dyn Error>> {
+        let name = self.rpm_field("name").unwrap_or_else(|| self.name.clone());
+        let summary = self
+            .rpm_field("summary")
+            .or_else(|| self.description.clone())
+            .ok_or(NotSpecified("package.description"))?;
+        let license = self
+            .rpm_field("license")
+            .or_else(|| self.license.clone())
+            .ok_or(NotSpecified("package.license"))?;
+        let release = self.rpm_field("release").unwrap_or_else(|| "1".to_owned());
+        let assets = self.rpm_assets();
+        if assets.is_empty() {
+            return Err("No [[bin]] targets to package".into());
+        }
+
+        let tag = |key: &str, value: &str| format!("{:<16}{value}", format!("{key}:"));
+        let mut lines = vec![tag("Name", &name)];
+        if let Some(epoch) = self.rpm_field("epoch") {
+            lines.push(tag("Epoch", &epoch));
+        }
+        lines.push(tag("Version", &rpm_version(&self.version)));
+        lines.push(tag("Release", &format!("{release}%{{?dist}}")));
+        lines.push(tag("Summary", &summary));
+        lines.push(tag("License", &license));
+        if let Some(url) = self.rpm_field("url").or_else(|| self.url.clone()) {
+            lines.push(tag("URL", &url));
+        }
+        lines.push(String::new());
+        lines.push(tag("BuildRequires", "cargo"));
+        lines.push(tag("BuildRequires", "rust"));
+
+        let requires = self
+            .rpm
+            .as_ref()
+            .and_then(|rpm| workspace::lookup(rpm, "requires"))
+            .and_then(Value::as_table);
+        for (dependency, version) in requires.into_iter().flatten() {
+            lines.push(match version.as_str() {
+                Some("*") | None => tag("Requires", dependency),
+                Some(version) => tag("Requires", &format!("{dependency} {version}")),
+            });
+        }
+
+        lines.extend([
+            String::new(),
+            "%description".to_owned(),
+            summary,
+            String::new(),
+            "%build".to_owned(),
+            "cargo build --release --locked".to_owned(),
+            String::new(),
+            "%install".to_owned(),
+        ]);
+        lines.extend(assets.iter().map(|asset| {
+            format!(
+                "install -Dm{} {} %{{buildroot}}{}",
+                asset.mode, asset.source, asset.dest
+            )
+        }));
+        lines.push(String::new());
+        lines.push("%files".to_owned());
+        lines.extend(assets.into_iter().map(|asset| asset.dest));
+
+        Ok(lines.join("\n"))
+    }
+
+    /// Arch also uses SPDX, the whole expression becomes the single item of `license`
+    fn pkgbuild(&self) -> Result<String, Box<dyn Error>> {
+        let description = self
+            .description
+            .as_deref()
+            .ok_or(NotSpecified("package.description"))?;
+        let license = self
+            .license
+            .as_deref()
+            .ok_or(NotSpecified("package.license"))?;
+        if self.bins.is_empty() {
+            return Err("No [[bin]] targets to package".into());
+        }
+
+        let mut lines = vec![
+            format!("pkgname={}", self.name.to_lowercase()),
+            format!("pkgver={}", pkgver(&self.version)),
+            "pkgrel=1".to_owned(),
+            format!("pkgdesc={}", quote(description)),
+            "arch=('x86_64')".to_owned(),
+        ];
+        if let Some(url) = &self.url {
+            lines.push(format!("url={}", quote(url)));
+        }
+        lines.extend([
+            format!("license=({})", quote(license)),
+            "depends=()".to_owned(),
+            "makedepends=('cargo')".to_owned(),
+            "source=()".to_owned(),
+            "sha256sums=()".to_owned(),
+            String::new(),
+            "build() {".to_owned(),
+            "  cargo build --release --locked".to_owned(),
+            "}".to_owned(),
+            String::new(),
+            "package() {".to_owned(),
+        ]);
+        lines.extend(
+            self.bins.iter().map(|bin| {
+                format!("  install -Dm755 target/release/{bin} \"$pkgdir/usr/bin/{bin}\"")
+            }),
+        );
+        lines.push("}".to_owned());
+
+        Ok(lines.join("\n"))
+    }
+
+    fn homebrew(&self) -> Result<String, Box<dyn Error>> {
+        let description = self
+            .description
+            .as_deref()
+            .ok_or(NotSpecified("package.description"))?;
+        if self.bins.is_empty() {
+            return Err("No [[bin]] targets to package".into());
+        }
+        let class: String = self
+            .name
+            .split(['-', '_'])
+            .map(|part| {
+                let mut chars = part.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let mut lines = vec![
+            format!("class {class} < Formula"),
+            format!("  desc {}", ruby_string(description)),
+        ];
+        if let Some(url) = &self.url {
+            lines.push(format!("  homepage {}", ruby_string(url)));
+        }
+        lines.extend([
+            format!("  url {}", ruby_string(&self.archive_url()?)),
+            format!("  sha256 {}", ruby_string(SHA256_PLACEHOLDER)),
+            format!(
+                "  license {}",
+                homebrew_license(&self.license_expression()?, true)
+            ),
+            String::new(),
+            "  depends_on \"rust\" => :build".to_owned(),
+            String::new(),
+            "  def install".to_owned(),
+            "    system \"cargo\", \"install\", *std_cargo_args".to_owned(),
+            "  end".to_owned(),
+            "end".to_owned(),
+        ]);
+
+        Ok(lines.join("\n"))
+    }
+
+    fn nix(&self) -> Result<String, Box<dyn Error>> {
+        let description = self
+            .description
+            .as_deref()
+            .ok_or(NotSpecified("package.description"))?;
+
+        let expression = self.license_expression()?;
+        let mut licenses = Vec::new();
+        collect_licenses(&expression, &mut licenses);
+        let licenses: Vec<_> = licenses
+            .into_iter()
+            .map(|(id, or_later)| nix_license(id, or_later))
+            .collect();
+        let license = match licenses.as_slice() {
+            [license] if license.starts_with('(') => license[1..license.len() - 1].to_owned(),
+            [license] => format!("lib.licenses.{license}"),
+            licenses => format!("with lib.licenses; [ {} ]", licenses.join(" ")),
+        };
+
+        let mut lines = vec![
+            "{ lib, rustPlatform, fetchzip }:".to_owned(),
+            String::new(),
+            "rustPlatform.buildRustPackage {".to_owned(),
+            format!("  pname = {};", nix_string(&self.name)),
+            format!("  version = {};", nix_string(&self.version)),
+            String::new(),
+            "  src = fetchzip {".to_owned(),
+            format!("    url = {};", nix_string(&self.archive_url()?)),
+            "    hash = lib.fakeHash;".to_owned(),
+            "  };".to_owned(),
+            String::new(),
+            "  cargoHash = lib.fakeHash;".to_owned(),
+            String::new(),
+            "  meta = {".to_owned(),
+            format!("    description = {};", nix_string(description)),
+        ];
+        if let Some(url) = &self.url {
+            lines.push(format!("    homepage = {};", nix_string(url)));
+        }
+        lines.push(format!("    license = {license};"));
+        if let Some(main_program) = &self.main_program {
+            lines.push(format!("    mainProgram = {};", nix_string(main_program)));
+        }
+        lines.extend(["  };".to_owned(), "}".to_owned()]);
+
+        Ok(lines.join("\n"))
+    }
+}
+
+/// The license DSL of Homebrew formulae, e.g. `any_of: ["MIT", "Apache-2.0"]`
+fn homebrew_license(expression: &Expression, top_level: bool) -> String {
+    let (op, items) = match expression {
+        Expression::License {
+            id,
+            or_later,
+            exception: Some(exception),
+        } => {
+            let license = Expression::License {
+                id: id.clone(),
+                or_later: *or_later,
+                exception: None,
+            };
+            let with = format!(
+                "{} => {{ with: {} }}",
+                ruby_string(&license.to_string()),
+                ruby_string(exception)
+            );
+            return match top_level {
+                true => with,
+                false => format!("{{ {with} }}"),
+            };
+        }
+        Expression::License { .. } => return ruby_string(&expression.to_string()),
+        Expression::And(items) => ("all_of", items),
+        Expression::Or(items) => ("any_of", items),
+    };
+
+    let items: Vec<_> = items
+        .iter()
+        .map(|item| homebrew_license(item, false))
+        .collect();
+    let list = format!("{op}: [{}]", items.join(", "));
+    match top_level {
+        true => list,
+        false => format!("{{ {list} }}"),
+    }
+}
+
+/// Every license of an expression with its `+` suffix, without duplicates
+fn collect_licenses<'a>(expression: &'a Expression, licenses: &mut Vec<(&'a str, bool)>) {
+    match expression {
+        Expression::License { id, or_later, .. } => {
+            if !licenses.contains(&(id.as_str(), *or_later)) {
+                licenses.push((id, *or_later))
+            }
+        }
+        Expression::And(items) | Expression::Or(items) => items
+            .iter()
+            .for_each(|item| collect_licenses(item, licenses)),
+    }
+}
+
+/// The attribute in `lib.licenses`, or a parenthesized lookup for licenses without a
+/// well-known attribute
+fn nix_license(id: &str, or_later: bool) -> String {
+    let id = match or_later {
+        true => format!("{}-or-later", id.trim_end_matches("-only")),
+        false => id.to_owned(),
+    };
+    let attribute = match id.as_str() {
+        "0BSD" => "bsd0",
+        "AGPL-3.0-only" => "agpl3Only",
+        "AGPL-3.0-or-later" => "agpl3Plus",
+        "Apache-2.0" => "asl20",
+        "BSD-2-Clause" => "bsd2",
+        "BSD-3-Clause" => "bsd3",
+        "BSL-1.0" => "boost",
+        "CC0-1.0" => "cc0",
+        "EPL-2.0" => "epl20",
+        "GPL-2.0-only" => "gpl2Only",
+        "GPL-2.0-or-later" => "gpl2Plus",
+        "GPL-3.0-only" => "gpl3Only",
+        "GPL-3.0-or-later" => "gpl3Plus",
+        "ISC" => "isc",
+        "LGPL-2.1-only" => "lgpl21Only",
+        "LGPL-2.1-or-later" => "lgpl21Plus",
+        "LGPL-3.0-only" => "lgpl3Only",
+        "LGPL-3.0-or-later" => "lgpl3Plus",
+        "MIT" => "mit",
+        "MIT-0" => "mit0",
+        "MPL-2.0" => "mpl20",
+        "Unicode-3.0" => "unicode-30",
+        "Unicode-DFS-2016" => "unicode-dfs-2016",
+        "Unlicense" => "unlicense",
+        "Zlib" => "zlib",
+        id => return format!("(lib.getLicenseFromSpdxId {})", nix_string(id)),
+    };
+    attribute.to_owned()
+}
+
+fn ruby_string(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace("#{", "\\#{");
+    format!("\"{escaped}\"")
+}
+
+fn nix_string(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace("${", "\\${");
+    format!("\"{escaped}\"")
+}
+
+/// RPM sorts `1.0.0~rc.1` before `1.0.0` and does not allow `-` in `Version`
+fn rpm_version(version: &str) -> String {
+    let (release, build) = match version.split_once('+') {
+        Some((release, build)) => (release, Some(build)),
+        None => (version, None),
+    };
+    let mut rpm = match release.split_once('-') {
+        Some((release, pre)) => format!("{release}~{}", pre.replace('-', "_")),
+        None => release.to_owned(),
+    };
+    if let Some(build) = build {
+        rpm.push_str(&format!("+{}", build.replace('-', "_")));
+    }
+    rpm
+}
+
+/// `pkgver` may only contain letters, digits, periods and underscores. The pre-release is
+/// appended without a separator, pacman only sorts `1.0.0rc.1` before `1.0.0` that way.
+fn pkgver(version: &str) -> String {
+    let (release, build) = match version.split_once('+') {
+        Some((release, build)) => (release, Some(build)),
+        None => (version, None),
+    };
+    let mut pkgver = match release.split_once('-') {
+        Some((release, pre)) => format!("{release}{}", pre.replace('-', "_")),
+        None => release.to_owned(),
+    };
+    if let Some(build) = build {
+        pkgver.push_str(&format!("_{}", build.replace('-', "_")));
+    }
+    pkgver
+}
+
+/// Single quoted for bash
+pub fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pkgver_ok() {
+        assert_eq!(pkgver("1.2.3"), "1.2.3");
+        assert_eq!(pkgver("0.4.0-beta.2"), "0.4.0beta.2");
+        assert_eq!(pkgver("1.2.3-rc.1+build.5"), "1.2.3rc.1_build.5");
+    }
+
+    #[test]
+    fn rpm_version_ok() {
+        assert_eq!(rpm_version("1.2.3"), "1.2.3");
+        assert_eq!(rpm_version("0.4.0-beta.2"), "0.4.0~beta.2");
+        assert_eq!(rpm_version("1.2.3-rc-1+build.5"), "1.2.3~rc_1+build.5");
+    }
+
+    #[test]
+    fn spdx_license_passthrough() {
+        let packaging = Packaging {
+            name: "tool".to_owned(),
+            version: "1.0.0".to_owned(),
+            description: Some("A tool".to_owned()),
+            url: None,
+            license: Some(spdx::normalize_or_raw(
+                "(MIT OR Apache-2.0) AND Unicode-3.0",
+            )),
+            bins: vec!["tool".to_owned()],
+            main_program: Some("tool".to_owned()),
+            repository: None,
+            tag: "v1.0.0".to_owned(),
+            rpm: None,
+        };
+
+        assert!(packaging
+            .rpm_spec()
+            .unwrap()
+            .contains("License:        Unicode-3.0 AND (MIT OR Apache-2.0)\n"));
+        assert!(packaging
+            .pkgbuild()
+            .unwrap()
+            .contains("license=('Unicode-3.0 AND (MIT OR Apache-2.0)')\n"));
+    }
+
+    #[test]
+    fn homebrew_license_ok() {
+        let license = |input: &str| homebrew_license(&spdx::parse(input).unwrap().expression, true);
+
+        assert_eq!(license("MIT"), r#""MIT""#);
+        assert_eq!(
+            license("MIT OR Apache-2.0"),
+            r#"any_of: ["MIT", "Apache-2.0"]"#
+        );
+        assert_eq!(
+            license("(MIT OR Apache-2.0) AND Unicode-3.0"),
+            r#"all_of: [{ any_of: ["MIT", "Apache-2.0"] }, "Unicode-3.0"]"#
+        );
+        assert_eq!(
+            license("GPL-2.0-only WITH Classpath-exception-2.0"),
+            r#""GPL-2.0-only" => { with: "Classpath-exception-2.0" }"#
+        );
+    }
+
+    #[test]
+    fn nix_license_ok() {
+        assert_eq!(nix_license("Apache-2.0", false), "asl20");
+        assert_eq!(nix_license("GPL-3.0-only", true), "gpl3Plus");
+        assert_eq!(
+            nix_license("EUPL-1.2", false),
+            r#"(lib.getLicenseFromSpdxId "EUPL-1.2")"#
+        );
+    }
+
+    #[test]
+    fn quote_ok() {
+        assert_eq!(quote("A $HOME tool"), "'A $HOME tool'");
+        assert_eq!(quote("It's"), r"'It'\''s'");
+    }
+}
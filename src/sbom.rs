@@ -0,0 +1,640 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    error::Error,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use cargo_toml::{Manifest, Package};
+use clap::ValueEnum;
+
+use crate::{
+    authors::Author,
+    error::NotSpecified,
+    json::Json,
+    lock::{self, Lockfile},
+    spdx,
+};
+
+#[derive(Debug, PartialEq, Clone, ValueEnum)]
+pub enum SbomFormat {
+    CyclonedxJson,
+    CyclonedxXml,
+    SpdxJson,
+    SpdxTag,
+}
+
+/// A package in the bill of materials, identified by its package URL. The URL of a crate
+/// from another registry or git carries the source as a qualifier.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Component {
+    pub name: String,
+    pub version: String,
+    pub purl: String,
+    pub checksum: Option<String>,
+    pub source: Option<String>,
+}
+
+impl Component {
+    fn new(name: &str, version: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            version: version.to_owned(),
+            purl: format!("pkg:cargo/{name}@{version}"),
+            checksum: None,
+            source: None,
+        }
+    }
+
+    fn locked(package: &lock::Package) -> Self {
+        let mut component = Self {
+            checksum: package.checksum.clone(),
+            source: package.source.clone(),
+            ..Self::new(&package.name, &package.version)
+        };
+        if let Some(qualifier) = component.source_qualifier() {
+            component.purl = format!("{}?{qualifier}", component.purl);
+        }
+        component
+    }
+
+    /// `repository_url` for other registries and `vcs_url` for git, crates.io is the default
+    /// repository of `pkg:cargo`
+    fn source_qualifier(&self) -> Option<String> {
+        let source = self.source.as_deref()?;
+        match lock::SourceKind::of(Some(source)) {
+            lock::SourceKind::Registry => {
+                let url = source.strip_prefix("registry+").unwrap_or(source);
+                Some(format!("repository_url={}", purl_encode(url)))
+            }
+            lock::SourceKind::Git => Some(format!(
+                "vcs_url={}",
+                purl_encode(&self.download_location())
+            )),
+            _ => None,
+        }
+    }
+
+    /// `SPDXRef-Package-name-version`, followed by the source when the package URL has one.
+    /// SPDX ids may only contain letters, numbers, `.` and `-`.
+    fn spdx_id(&self) -> String {
+        let mut id = format!("{}-{}", self.name, self.version);
+        if let (Some(_), Some(source)) = (self.purl.split_once('?'), &self.source) {
+            let source = source.split_once('+').map_or(source.as_str(), |(_, s)| s);
+            let source = source.split_once("://").map_or(source, |(_, s)| s);
+            id = format!("{id}-{source}");
+        }
+
+        let id: String = id
+            .chars()
+            .map(
+                |c| match c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                    true => c,
+                    false => '-',
+                },
+            )
+            .collect();
+        format!("SPDXRef-Package-{id}")
+    }
+
+    /// Where the crate can be downloaded from, `NOASSERTION` for path dependencies and
+    /// other registries
+    fn download_location(&self) -> String {
+        match (lock::SourceKind::of(self.source.as_deref()), &self.source) {
+            (lock::SourceKind::CratesIo, _) => format!(
+                "https://crates.io/api/v1/crates/{}/{}/download",
+                self.name, self.version
+            ),
+            // `git+url?branch=x#commit` in Cargo.lock, `git+url@commit` in SPDX
+            (lock::SourceKind::Git, Some(source)) => {
+                let (url, commit) = source.split_once('#').unwrap_or((source, ""));
+                let url = url.split('?').next().unwrap_or(url);
+                match commit {
+                    "" => url.to_owned(),
+                    commit => format!("{url}@{commit}"),
+                }
+            }
+            _ => "NOASSERTION".to_owned(),
+        }
+    }
+}
+
+/// Everything known offline about a package and its locked dependencies
+#[derive(Debug, PartialEq)]
+pub struct Sbom {
+    pub root: Component,
+    pub application: bool,
+    pub description: Option<String>,
+    pub authors: Vec<Author>,
+    /// SPDX expression, normalized when it can be parsed
+    pub license: Option<String>,
+    pub repository: Option<String>,
+    pub homepage: Option<String>,
+    pub components: Vec<Component>,
+    /// Package URLs of each component and the components it depends on
+    pub dependencies: Vec<(String, Vec<String>)>,
+}
+
+impl Sbom {
+    pub fn new(manifest: &Manifest, lockfile: &Lockfile) -> Result<Self, Box<dyn Error>> {
+        let package: &Package = manifest.package.as_ref().ok_or(NotSpecified("package"))?;
+        let root = lockfile
+            .packages
+            .iter()
+            .find(|p| p.name == package.name() && p.source.is_none())
+            .ok_or_else(|| format!("`{}` not in Cargo.lock", package.name()))?;
+
+        let reachable = lockfile.closure(root);
+        let locked: Vec<&lock::Package> = lockfile
+            .packages
+            .iter()
+            .filter(|p| reachable.contains(&p.key()))
+            .collect();
+
+        let purl = |p: &lock::Package| Component::locked(p).purl;
+        let dependencies = std::iter::once(root)
+            .chain(locked.iter().copied())
+            .map(|p| {
+                let depends_on: BTreeSet<_> = p
+                    .dependencies
+                    .iter()
+                    .filter_map(|d| lockfile.resolve(d))
+                    .map(purl)
+                    .collect();
+                (purl(p), depends_on.into_iter().collect())
+            })
+            .collect();
+
+        let license = package.license().map(spdx::normalize_or_raw);
+
+        Ok(Self {
+            root: Component {
+                source: package.repository().map(|r| format!("git+{r}")),
+                ..Component::new(package.name(), package.version())
+            },
+            application: !manifest.bin.is_empty(),
+            description: package.description().map(str::to_owned),
+            authors: package.authors().iter().map(|a| Author::parse(a)).collect(),
+            license,
+            repository: package.repository().map(str::to_owned),
+            homepage: package.homepage().map(str::to_owned),
+            components: locked.iter().map(|p| Component::locked(p)).collect(),
+            dependencies,
+        })
+    }
+
+    /// `created` is only used by SPDX, in seconds since the Unix epoch
+    pub fn render(&self, format: &SbomFormat, created: u64) -> String {
+        match format {
+            SbomFormat::CyclonedxJson => self.cyclonedx_json().to_string(),
+            SbomFormat::CyclonedxXml => self.cyclonedx_xml(),
+            SbomFormat::SpdxJson => self.spdx_json(created).to_string(),
+            SbomFormat::SpdxTag => self.spdx_tag(created),
+        }
+    }
+
+    fn component_type(&self) -> &'static str {
+        match self.application {
+            true => "application",
+            false => "library",
+        }
+    }
+
+    fn cyclonedx_json(&self) -> Json {
+        let component = |c: &Component, kind: &str| {
+            let mut fields = vec![
+                ("type", Json::from(kind)),
+                ("bom-ref", c.purl.as_str().into()),
+                ("name", c.name.as_str().into()),
+                ("version", c.version.as_str().into()),
+            ];
+            if let Some(checksum) = &c.checksum {
+                let hash = Json::object([
+                    ("alg", Json::from("SHA-256")),
+                    ("content", checksum.as_str().into()),
+                ]);
+                fields.push(("hashes", Json::Array(vec![hash])));
+            }
+            fields.push(("purl", c.purl.as_str().into()));
+            fields
+        };
+
+        let mut root = component(&self.root, self.component_type());
+        if !self.authors.is_empty() {
+            let authors = self.authors.iter().map(|author| {
+                let mut contact = Vec::new();
+                contact.extend(author.name.clone().map(|n| ("name", Json::from(n))));
+                contact.extend(author.email.clone().map(|e| ("email", Json::from(e))));
+                Json::object(contact)
+            });
+            root.insert(2, ("authors", authors.collect()));
+        }
+        if let Some(description) = &self.description {
+            root.push(("description", description.as_str().into()));
+        }
+        if let Some(license) = &self.license {
+            let expression = Json::object([("expression", Json::from(license.as_str()))]);
+            root.push(("licenses", Json::Array(vec![expression])));
+        }
+        if let Some(repository) = &self.repository {
+            let reference = Json::object([
+                ("type", Json::from("vcs")),
+                ("url", repository.as_str().into()),
+            ]);
+            root.push(("externalReferences", Json::Array(vec![reference])));
+        }
+
+        Json::object([
+            ("bomFormat", Json::from("CycloneDX")),
+            ("specVersion", "1.6".into()),
+            ("version", 1.into()),
+            (
+                "metadata",
+                Json::object([("component", Json::object(root))]),
+            ),
+            (
+                "components",
+                self.components
+                    .iter()
+                    .map(|c| Json::object(component(c, "library")))
+                    .collect(),
+            ),
+            (
+                "dependencies",
+                self.dependencies
+                    .iter()
+                    .map(|(purl, depends_on)| {
+                        Json::object([
+                            ("ref", Json::from(purl.as_str())),
+                            ("dependsOn", depends_on.iter().map(String::as_str).collect()),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ])
+    }
+
+    fn cyclonedx_xml(&self) -> String {
+        let mut lines = vec![
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_owned(),
+            r#"<bom xmlns="http://cyclonedx.org/schema/bom/1.6" version="1">"#.to_owned(),
+            "  <metadata>".to_owned(),
+        ];
+        lines.extend(self.xml_component(&self.root, self.component_type(), true));
+        lines.push("  </metadata>".to_owned());
+
+        lines.push("  <components>".to_owned());
+        for component in &self.components {
+            lines.extend(self.xml_component(component, "library", false));
+        }
+        lines.push("  </components>".to_owned());
+
+        lines.push("  <dependencies>".to_owned());
+        for (purl, depends_on) in &self.dependencies {
+            if depends_on.is_empty() {
+                lines.push(format!("    <dependency ref=\"{}\"/>", xml(purl)));
+                continue;
+            }
+            lines.push(format!("    <dependency ref=\"{}\">", xml(purl)));
+            for dep in depends_on {
+                lines.push(format!("      <dependency ref=\"{}\"/>", xml(dep)));
+            }
+            lines.push("    </dependency>".to_owned());
+        }
+        lines.push("  </dependencies>".to_owned());
+        lines.push("</bom>".to_owned());
+        lines.join("\n")
+    }
+
+    /// Elements in the order the CycloneDX schema requires. Only the metadata component
+    /// carries the package metadata.
+    fn xml_component(&self, c: &Component, kind: &str, metadata: bool) -> Vec<String> {
+        let element = |name: &str, value: &str| format!("      <{name}>{}</{name}>", xml(value));
+
+        let mut lines = vec![format!(
+            "    <component type=\"{kind}\" bom-ref=\"{}\">",
+            xml(&c.purl)
+        )];
+
+        if metadata && !self.authors.is_empty() {
+            lines.push("      <authors>".to_owned());
+            for author in &self.authors {
+                let mut contact = String::new();
+                if let Some(name) = &author.name {
+                    contact.push_str(&format!("<name>{}</name>", xml(name)));
+                }
+                if let Some(email) = &author.email {
+                    contact.push_str(&format!("<email>{}</email>", xml(email)));
+                }
+                lines.push(format!("        <author>{contact}</author>"));
+            }
+            lines.push("      </authors>".to_owned());
+        }
+        lines.push(element("name", &c.name));
+        lines.push(element("version", &c.version));
+        if let (true, Some(description)) = (metadata, &self.description) {
+            lines.push(element("description", description));
+        }
+        if let Some(checksum) = &c.checksum {
+            lines.push(format!(
+                "      <hashes><hash alg=\"SHA-256\">{}</hash></hashes>",
+                xml(checksum)
+            ));
+        }
+        if let (true, Some(license)) = (metadata, &self.license) {
+            lines.push(format!(
+                "      <licenses><expression>{}</expression></licenses>",
+                xml(license)
+            ));
+        }
+        lines.push(element("purl", &c.purl));
+        if let (true, Some(repository)) = (metadata, &self.repository) {
+            lines.push(format!(
+                "      <externalReferences><reference type=\"vcs\"><url>{}</url></reference></externalReferences>",
+                xml(repository)
+            ));
+        }
+        lines.push("    </component>".to_owned());
+        lines
+    }
+
+    fn spdx_name(&self) -> String {
+        format!("{}-{}", self.root.name, self.root.version)
+    }
+
+    fn spdx_namespace(&self, created: u64) -> String {
+        format!("https://spdx.org/spdxdocs/{}-{created}", self.spdx_name())
+    }
+
+    /// Relationships as `(element, type, related element)`
+    fn spdx_relationships(&self) -> Vec<(String, &'static str, String)> {
+        let ids: HashMap<&str, String> = self
+            .spdx_packages()
+            .into_iter()
+            .map(|(c, _)| (c.purl.as_str(), c.spdx_id()))
+            .collect();
+
+        let mut relationships = vec![(
+            "SPDXRef-DOCUMENT".to_owned(),
+            "DESCRIBES",
+            self.root.spdx_id(),
+        )];
+        for (purl, depends_on) in &self.dependencies {
+            for dep in depends_on {
+                relationships.push((
+                    ids[purl.as_str()].clone(),
+                    "DEPENDS_ON",
+                    ids[dep.as_str()].clone(),
+                ));
+            }
+        }
+        relationships
+    }
+
+    /// The root package first, flagged `true`, then all components
+    fn spdx_packages(&self) -> Vec<(&Component, bool)> {
+        std::iter::once((&self.root, true))
+            .chain(self.components.iter().map(|c| (c, false)))
+            .collect()
+    }
+
+    /// Only the license of the root package is known offline
+    fn spdx_license(&self, root: bool) -> &str {
+        match (root, &self.license) {
+            (true, Some(license)) => license,
+            _ => "NOASSERTION",
+        }
+    }
+
+    fn spdx_json(&self, created: u64) -> Json {
+        let packages = self.spdx_packages().into_iter().map(|(c, root)| {
+            let mut fields = vec![
+                ("SPDXID", Json::from(c.spdx_id())),
+                ("name", c.name.as_str().into()),
+                ("versionInfo", c.version.as_str().into()),
+                ("downloadLocation", c.download_location().into()),
+                ("filesAnalyzed", Json::Bool(false)),
+            ];
+            if let Some(checksum) = &c.checksum {
+                let checksum = Json::object([
+                    ("algorithm", Json::from("SHA256")),
+                    ("checksumValue", checksum.as_str().into()),
+                ]);
+                fields.push(("checksums", Json::Array(vec![checksum])));
+            }
+            if root {
+                fields.extend(self.homepage.as_deref().map(|h| ("homepage", h.into())));
+            }
+            fields.push(("licenseConcluded", "NOASSERTION".into()));
+            fields.push(("licenseDeclared", self.spdx_license(root).into()));
+            fields.push(("copyrightText", "NOASSERTION".into()));
+            if root {
+                fields.extend(
+                    self.description
+                        .as_deref()
+                        .map(|d| ("description", d.into())),
+                );
+            }
+            let purl = Json::object([
+                ("referenceCategory", Json::from("PACKAGE-MANAGER")),
+                ("referenceType", "purl".into()),
+                ("referenceLocator", c.purl.as_str().into()),
+            ]);
+            fields.push(("externalRefs", Json::Array(vec![purl])));
+            Json::object(fields)
+        });
+
+        let relationships =
+            self.spdx_relationships()
+                .into_iter()
+                .map(|(element, kind, related)| {
+                    Json::object([
+                        ("spdxElementId", Json::from(element)),
+                        ("relationshipType", kind.into()),
+                        ("relatedSpdxElement", related.into()),
+                    ])
+                });
+
+        Json::object([
+            ("spdxVersion", Json::from("SPDX-2.3")),
+            ("dataLicense", "CC0-1.0".into()),
+            ("SPDXID", "SPDXRef-DOCUMENT".into()),
+            ("name", self.spdx_name().into()),
+            ("documentNamespace", self.spdx_namespace(created).into()),
+            (
+                "creationInfo",
+                Json::object([
+                    ("created", Json::from(timestamp(created))),
+                    ("creators", vec![creator()].into_iter().collect()),
+                ]),
+            ),
+            ("packages", packages.collect()),
+            ("relationships", relationships.collect()),
+        ])
+    }
+
+    fn spdx_tag(&self, created: u64) -> String {
+        let mut lines = vec![
+            "SPDXVersion: SPDX-2.3".to_owned(),
+            "DataLicense: CC0-1.0".to_owned(),
+            "SPDXID: SPDXRef-DOCUMENT".to_owned(),
+            format!("DocumentName: {}", self.spdx_name()),
+            format!("DocumentNamespace: {}", self.spdx_namespace(created)),
+            format!("Creator: {}", creator()),
+            format!("Created: {}", timestamp(created)),
+        ];
+
+        for (c, root) in self.spdx_packages() {
+            lines.push(String::new());
+            lines.push(format!("PackageName: {}", c.name));
+            lines.push(format!("SPDXID: {}", c.spdx_id()));
+            lines.push(format!("PackageVersion: {}", c.version));
+            lines.push(format!(
+                "PackageDownloadLocation: {}",
+                c.download_location()
+            ));
+            lines.push("FilesAnalyzed: false".to_owned());
+            if let Some(checksum) = &c.checksum {
+                lines.push(format!("PackageChecksum: SHA256: {checksum}"));
+            }
+            if let (true, Some(homepage)) = (root, &self.homepage) {
+                lines.push(format!("PackageHomePage: {homepage}"));
+            }
+            lines.push("PackageLicenseConcluded: NOASSERTION".to_owned());
+            lines.push(format!(
+                "PackageLicenseDeclared: {}",
+                self.spdx_license(root)
+            ));
+            lines.push("PackageCopyrightText: NOASSERTION".to_owned());
+            if let (true, Some(description)) = (root, &self.description) {
+                lines.push(format!("PackageDescription: <text>{description}</text>"));
+            }
+            lines.push(format!("ExternalRef: PACKAGE-MANAGER purl {}", c.purl));
+        }
+
+        lines.push(String::new());
+        for (element, kind, related) in self.spdx_relationships() {
+            lines.push(format!("Relationship: {element} {kind} {related}"));
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Seconds since the Unix epoch from `SOURCE_DATE_EPOCH` for reproducible builds, or the
+/// current time
+pub fn creation_time() -> Result<u64, Box<dyn Error>> {
+    match std::env::var("SOURCE_DATE_EPOCH") {
+        Ok(epoch) => Ok(epoch
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid SOURCE_DATE_EPOCH `{epoch}`"))?),
+        Err(_) => Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs()),
+    }
+}
+
+fn creator() -> String {
+    format!("Tool: cargo-get-{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// Percent-encode a purl qualifier value, keeping `:` and `/` readable
+fn purl_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(
+            |b| match b.is_ascii_alphanumeric() || b".-_~:/".contains(&b) {
+                true => (b as char).to_string(),
+                false => format!("%{b:02X}"),
+            },
+        )
+        .collect()
+}
+
+/// ISO 8601 UTC timestamp, e.g. `2024-01-31T12:00:00Z`
+fn timestamp(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let rem = secs % 86400;
+
+    // Civil date from days since 1970-01-01, after Howard Hinnant's `civil_from_days`
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+fn xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn timestamp_ok() {
+        assert_eq!(timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(timestamp(951782400), "2000-02-29T00:00:00Z");
+        assert_eq!(timestamp(1706702400), "2024-01-31T12:00:00Z");
+    }
+
+    fn package(source: Option<&str>) -> lock::Package {
+        lock::Package {
+            name: "serde_json".to_owned(),
+            version: "1.0.0+build".to_owned(),
+            source: source.map(str::to_owned),
+            checksum: None,
+            dependencies: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn spdx_id_ok() {
+        let crates_io = package(Some(
+            "registry+https://github.com/rust-lang/crates.io-index",
+        ));
+        assert_eq!(
+            Component::locked(&crates_io).spdx_id(),
+            "SPDXRef-Package-serde-json-1.0.0-build"
+        );
+        let registry = package(Some("registry+https://registry.example.com/index"));
+        assert_eq!(
+            Component::locked(&registry).spdx_id(),
+            "SPDXRef-Package-serde-json-1.0.0-build-registry.example.com-index"
+        );
+    }
+
+    #[test]
+    fn purl_source_qualifier() {
+        let registry = package(Some("registry+https://registry.example.com/index"));
+        assert_eq!(
+            Component::locked(&registry).purl,
+            "pkg:cargo/serde_json@1.0.0+build?repository_url=https://registry.example.com/index"
+        );
+        let git = package(Some(
+            "git+https://github.com/serde-rs/json?branch=master#a1b2c3",
+        ));
+        assert_eq!(
+            Component::locked(&git).purl,
+            "pkg:cargo/serde_json@1.0.0+build?vcs_url=git%2Bhttps://github.com/serde-rs/json%40a1b2c3"
+        );
+        assert_eq!(
+            Component::locked(&package(None)).purl,
+            "pkg:cargo/serde_json@1.0.0+build"
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use cargo_toml::{Manifest, Value};
+
+use crate::{git::Revision, workspace};
+
+/// Where manifests are read from
+#[derive(Debug)]
+pub enum Source {
+    WorkingTree,
+    Git(Revision),
+}
+
+impl Source {
+    pub fn is_file(&self, path: &Path) -> bool {
+        match self {
+            Self::WorkingTree => path.is_file(),
+            Self::Git(revision) => revision.is_file(path),
+        }
+    }
+
+    pub fn is_dir(&self, path: &Path) -> bool {
+        match self {
+            Self::WorkingTree => path.is_dir(),
+            Self::Git(revision) => revision.is_dir(path),
+        }
+    }
+
+    pub fn read_to_string(&self, path: &Path) -> Result<String, Box<dyn Error>> {
+        match self {
+            Self::WorkingTree => Ok(std::fs::read_to_string(path)?),
+            Self::Git(revision) => revision.read_to_string(path),
+        }
+    }
+
+    /// Sorted subdirectories of `dir`
+    pub fn subdirs(&self, dir: &Path) -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = match self {
+            Self::WorkingTree => std::fs::read_dir(dir)
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .collect(),
+            Self::Git(revision) => revision
+                .entries(dir)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|(_, is_dir)| *is_dir)
+                .map(|(name, _)| dir.join(name))
+                .collect(),
+        };
+        dirs.sort();
+        dirs
+    }
+
+    /// Parse a manifest without completing it
+    pub fn raw_manifest(&self, path: &Path) -> Result<Manifest, Box<dyn Error>> {
+        let content = self.read_to_string(path)?;
+        Ok(Manifest::from_str(&content)
+            .map_err(|err| format!("Failed to parse {}: {err}", path.display()))?)
+    }
+
+    /// Parse a manifest and complete workspace inheritance and target autodiscovery, like
+    /// `Manifest::from_path` does for the working tree
+    pub fn manifest(&self, path: &Path) -> Result<Manifest, Box<dyn Error>> {
+        let revision = match self {
+            Self::WorkingTree => return Ok(Manifest::from_path(path)?),
+            Self::Git(revision) => revision,
+        };
+
+        let mut manifest = self.raw_manifest(path)?;
+        let dir = revision.dir(path.parent().ok_or("Invalid manifest path")?);
+
+        match workspace::find_root(self, path, &manifest)? {
+            Some(root) if root != path => {
+                let workspace = self.raw_manifest(&root)?;
+                let root_dir = root.parent().ok_or("Invalid manifest path")?;
+                manifest.complete_from_abstract_filesystem(dir, Some((&workspace, root_dir)))?;
+            }
+            _ => manifest.complete_from_abstract_filesystem::<Value, _>(dir, None)?,
+        }
+
+        Ok(manifest)
+    }
+}
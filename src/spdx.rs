@@ -0,0 +1,1358 @@
+use std::fmt;
+
+/// A parsed SPDX license expression
+#[derive(Debug, PartialEq, Clone)]
+pub enum Expression {
+    License {
+        id: String,
+        or_later: bool,
+        exception: Option<String>,
+    },
+    And(Vec<Expression>),
+    Or(Vec<Expression>),
+}
+
+/// Something in a license expression that `--check` rejects
+#[derive(Debug, PartialEq, Clone)]
+pub enum Issue {
+    UnknownLicense(String),
+    UnknownException(String),
+    DeprecatedLicense { id: String, replacement: String },
+    DeprecatedSyntax { found: String, replacement: String },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownLicense(id) => write!(f, "unknown license identifier `{id}`"),
+            Self::UnknownException(id) => write!(f, "unknown license exception `{id}`"),
+            Self::DeprecatedLicense { id, replacement } => {
+                write!(
+                    f,
+                    "deprecated license identifier `{id}`, use `{replacement}`"
+                )
+            }
+            Self::DeprecatedSyntax { found, replacement } => {
+                write!(f, "deprecated syntax `{found}`, use `{replacement}`")
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseError(pub String);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid license expression: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug)]
+pub struct CheckFailed(pub Vec<Issue>);
+
+impl fmt::Display for CheckFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let issues: Vec<_> = self.0.iter().map(ToString::to_string).collect();
+        write!(f, "License check failed: {}", issues.join("; "))
+    }
+}
+
+impl std::error::Error for CheckFailed {}
+
+/// The result of parsing a license expression. Issues are collected instead of failing
+/// early, so the expression can still be normalized.
+#[derive(Debug)]
+pub struct Parsed {
+    pub expression: Expression,
+    pub issues: Vec<Issue>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Id(String),
+    And,
+    Or,
+    With,
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Id(id) => write!(f, "`{id}`"),
+            Self::And => write!(f, "`AND`"),
+            Self::Or => write!(f, "`OR`"),
+            Self::With => write!(f, "`WITH`"),
+            Self::Open => write!(f, "`(`"),
+            Self::Close => write!(f, "`)`"),
+        }
+    }
+}
+
+/// The normalized expression, or the input unchanged when it cannot be parsed
+pub fn normalize_or_raw(input: &str) -> String {
+    match parse(input) {
+        Ok(parsed) => parsed.expression.normalize().to_string(),
+        Err(_) => input.to_owned(),
+    }
+}
+
+pub fn parse(input: &str) -> Result<Parsed, ParseError> {
+    let mut issues = Vec::new();
+    let tokens = tokenize(input, &mut issues)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        issues,
+    };
+
+    let expression = parser.or()?;
+
+    if let Some(token) = parser.tokens.get(parser.pos) {
+        return Err(ParseError(format!("unexpected {token} in `{input}`")));
+    }
+
+    Ok(Parsed {
+        expression,
+        issues: parser.issues,
+    })
+}
+
+fn tokenize(input: &str, issues: &mut Vec<Issue>) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '/' => {
+                chars.next();
+                issues.push(Issue::DeprecatedSyntax {
+                    found: "/".to_owned(),
+                    replacement: "OR".to_owned(),
+                });
+                tokens.push(Token::Or);
+            }
+            c if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+' | ':') => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+' | ':') {
+                        word.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+
+                let operator = match word.to_uppercase().as_str() {
+                    "AND" => Some(Token::And),
+                    "OR" => Some(Token::Or),
+                    "WITH" => Some(Token::With),
+                    _ => None,
+                };
+
+                match operator {
+                    Some(op) => {
+                        let upper = word.to_uppercase();
+                        if word != upper {
+                            issues.push(Issue::DeprecatedSyntax {
+                                found: word,
+                                replacement: upper,
+                            });
+                        }
+                        tokens.push(op);
+                    }
+                    None => tokens.push(Token::Id(word)),
+                }
+            }
+            c => return Err(ParseError(format!("unexpected character `{c}`"))),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    issues: Vec<Issue>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn or(&mut self) -> Result<Expression, ParseError> {
+        let mut items = vec![self.and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            items.push(self.and()?);
+        }
+        Ok(flatten(items, Expression::Or))
+    }
+
+    fn and(&mut self) -> Result<Expression, ParseError> {
+        let mut items = vec![self.primary()?];
+        while self.peek() == Some(&Token::And) {
+            self.next();
+            items.push(self.primary()?);
+        }
+        Ok(flatten(items, Expression::And))
+    }
+
+    fn primary(&mut self) -> Result<Expression, ParseError> {
+        match self.next() {
+            Some(Token::Open) => {
+                let inner = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(inner),
+                    _ => Err(ParseError("missing closing parenthesis".to_owned())),
+                }
+            }
+            Some(Token::Id(word)) => {
+                let mut license = self.license(&word);
+                if self.peek() == Some(&Token::With) {
+                    self.next();
+                    let Some(Token::Id(exception)) = self.next() else {
+                        return Err(ParseError("expected exception after `WITH`".to_owned()));
+                    };
+                    if let Expression::License { exception: e, .. } = &mut license {
+                        *e = Some(self.exception(&exception));
+                    }
+                }
+                Ok(license)
+            }
+            Some(token) => Err(ParseError(format!("unexpected {token}"))),
+            None => Err(ParseError("unexpected end of expression".to_owned())),
+        }
+    }
+
+    fn license(&mut self, word: &str) -> Expression {
+        if let Some(reference) = license_ref(word) {
+            return Expression::License {
+                id: reference,
+                or_later: false,
+                exception: None,
+            };
+        }
+
+        let (id, mut or_later) = match word.strip_suffix('+') {
+            Some(id) => (id, true),
+            None => (word, false),
+        };
+
+        let mut exception = None;
+        let id = match lookup(LICENSES, id) {
+            Some(canonical) => canonical.to_owned(),
+            None => match DEPRECATED
+                .iter()
+                .find(|(old, _)| old.eq_ignore_ascii_case(id))
+            {
+                Some((_, new)) => {
+                    let replacement = if or_later {
+                        or_later = false;
+                        new.replace("-only", "-or-later")
+                    } else {
+                        new.to_string()
+                    };
+                    self.issues.push(Issue::DeprecatedLicense {
+                        id: word.to_owned(),
+                        replacement: replacement.clone(),
+                    });
+                    // Identifiers like `GPL-2.0-with-classpath-exception` became an exception
+                    match replacement.split_once(" WITH ") {
+                        Some((id, with)) => {
+                            exception = Some(with.to_owned());
+                            id.to_owned()
+                        }
+                        None => replacement,
+                    }
+                }
+                None => {
+                    self.issues.push(Issue::UnknownLicense(id.to_owned()));
+                    id.to_owned()
+                }
+            },
+        };
+
+        Expression::License {
+            id,
+            or_later,
+            exception,
+        }
+    }
+
+    fn exception(&mut self, word: &str) -> String {
+        match lookup(EXCEPTIONS, word) {
+            Some(canonical) => canonical.to_owned(),
+            None => {
+                self.issues.push(Issue::UnknownException(word.to_owned()));
+                word.to_owned()
+            }
+        }
+    }
+}
+
+fn license_ref(word: &str) -> Option<String> {
+    ["LicenseRef-", "DocumentRef-"]
+        .iter()
+        .find(|prefix| {
+            word.len() > prefix.len() && word[..prefix.len()].eq_ignore_ascii_case(prefix)
+        })
+        .map(|prefix| format!("{prefix}{}", &word[prefix.len()..]))
+}
+
+fn lookup(list: &'static [&'static str], id: &str) -> Option<&'static str> {
+    list.iter().copied().find(|l| l.eq_ignore_ascii_case(id))
+}
+
+fn flatten(items: Vec<Expression>, op: fn(Vec<Expression>) -> Expression) -> Expression {
+    if items.len() == 1 {
+        return items.into_iter().next().unwrap();
+    }
+    op(items)
+}
+
+impl Expression {
+    /// All license identifiers in order of appearance, without duplicates
+    pub fn ids(&self) -> Vec<&str> {
+        let mut ids = Vec::new();
+        self.collect_ids(&mut ids);
+        ids
+    }
+
+    fn collect_ids<'a>(&'a self, ids: &mut Vec<&'a str>) {
+        match self {
+            Self::License { id, .. } => {
+                if !ids.contains(&id.as_str()) {
+                    ids.push(id)
+                }
+            }
+            Self::And(items) | Self::Or(items) => {
+                items.iter().for_each(|item| item.collect_ids(ids))
+            }
+        }
+    }
+
+    /// Whether the expression can be complied with using only licenses `allowed` accepts
+    pub fn satisfies(&self, allowed: &dyn Fn(&str) -> bool) -> bool {
+        match self {
+            Self::License { id, .. } => allowed(id),
+            Self::And(items) => items.iter().all(|item| item.satisfies(allowed)),
+            Self::Or(items) => items.iter().any(|item| item.satisfies(allowed)),
+        }
+    }
+
+    /// The canonical form: nested operators of the same kind are merged, duplicates are
+    /// removed and operands are ordered by how common the license is on crates.io.
+    pub fn normalize(&self) -> Self {
+        match self {
+            Self::License { .. } => self.clone(),
+            Self::And(items) => Self::normalize_op(items, Self::And),
+            Self::Or(items) => Self::normalize_op(items, Self::Or),
+        }
+    }
+
+    fn normalize_op(items: &[Self], op: fn(Vec<Self>) -> Self) -> Self {
+        let mut out: Vec<Self> = Vec::new();
+
+        for item in items.iter().map(Self::normalize) {
+            let nested = match (&item, op(Vec::new())) {
+                (Self::And(inner), Self::And(_)) | (Self::Or(inner), Self::Or(_)) => inner.clone(),
+                _ => vec![item],
+            };
+
+            for item in nested {
+                if !out.contains(&item) {
+                    out.push(item);
+                }
+            }
+        }
+
+        out.sort_by_key(Self::rank);
+        flatten(out, op)
+    }
+
+    fn rank(&self) -> (usize, String) {
+        match self {
+            Self::License { id, .. } => (
+                COMMON
+                    .iter()
+                    .position(|c| *c == id.as_str())
+                    .unwrap_or(COMMON.len()),
+                id.to_lowercase(),
+            ),
+            _ => (COMMON.len() + 1, String::new()),
+        }
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::License {
+                id,
+                or_later,
+                exception,
+            } => {
+                write!(f, "{id}")?;
+                if *or_later {
+                    write!(f, "+")?;
+                }
+                if let Some(exception) = exception {
+                    write!(f, " WITH {exception}")?;
+                }
+                Ok(())
+            }
+            Self::And(items) => write_op(f, items, "AND"),
+            Self::Or(items) => write_op(f, items, "OR"),
+        }
+    }
+}
+
+fn write_op(f: &mut fmt::Formatter<'_>, items: &[Expression], op: &str) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            write!(f, " {op} ")?;
+        }
+        match item {
+            // AND binds tighter than OR, so only a nested OR needs parentheses
+            Expression::Or(_) if op == "AND" => write!(f, "({item})")?,
+            _ => write!(f, "{item}")?,
+        }
+    }
+    Ok(())
+}
+
+/// Operand order used by [`Expression::normalize`]. Licenses not in this list follow in
+/// alphabetical order.
+const COMMON: &[&str] = &[
+    "MIT",
+    "Apache-2.0",
+    "BSD-3-Clause",
+    "BSD-2-Clause",
+    "ISC",
+    "Zlib",
+    "Unlicense",
+    "MPL-2.0",
+    "0BSD",
+    "CC0-1.0",
+];
+
+/// Deprecated identifiers with their replacement. A trailing `+` on a deprecated
+/// identifier maps to the `-or-later` variant.
+const DEPRECATED: &[(&str, &str)] = &[
+    ("AGPL-1.0", "AGPL-1.0-only"),
+    ("AGPL-3.0", "AGPL-3.0-only"),
+    ("BSD-2-Clause-FreeBSD", "BSD-2-Clause"),
+    ("BSD-2-Clause-NetBSD", "BSD-2-Clause"),
+    ("bzip2-1.0.5", "bzip2-1.0.6"),
+    ("eCos-2.0", "GPL-2.0-or-later WITH eCos-exception-2.0"),
+    ("GFDL-1.1", "GFDL-1.1-only"),
+    ("GFDL-1.2", "GFDL-1.2-only"),
+    ("GFDL-1.3", "GFDL-1.3-only"),
+    ("GPL-1.0", "GPL-1.0-only"),
+    ("GPL-2.0", "GPL-2.0-only"),
+    (
+        "GPL-2.0-with-autoconf-exception",
+        "GPL-2.0-only WITH Autoconf-exception-2.0",
+    ),
+    (
+        "GPL-2.0-with-bison-exception",
+        "GPL-2.0-or-later WITH Bison-exception-2.2",
+    ),
+    (
+        "GPL-2.0-with-classpath-exception",
+        "GPL-2.0-only WITH Classpath-exception-2.0",
+    ),
+    (
+        "GPL-2.0-with-font-exception",
+        "GPL-2.0-only WITH Font-exception-2.0",
+    ),
+    (
+        "GPL-2.0-with-GCC-exception",
+        "GPL-2.0-only WITH GCC-exception-2.0",
+    ),
+    ("GPL-3.0", "GPL-3.0-only"),
+    (
+        "GPL-3.0-with-autoconf-exception",
+        "GPL-3.0-only WITH Autoconf-exception-3.0",
+    ),
+    (
+        "GPL-3.0-with-GCC-exception",
+        "GPL-3.0-only WITH GCC-exception-3.1",
+    ),
+    ("LGPL-2.0", "LGPL-2.0-only"),
+    ("LGPL-2.1", "LGPL-2.1-only"),
+    ("LGPL-3.0", "LGPL-3.0-only"),
+    ("Nunit", "zlib-acknowledgement"),
+    ("StandardML-NJ", "SMLNJ"),
+    (
+        "wxWindows",
+        "LGPL-2.0-or-later WITH WxWindows-exception-3.1",
+    ),
+];
+
+/// License identifiers of the SPDX license list 3.27.0, without the deprecated ones that
+/// have a replacement in [`DEPRECATED`]
+const LICENSES: &[&str] = &[
+    "0BSD",
+    "3D-Slicer-1.0",
+    "AAL",
+    "Abstyles",
+    "AdaCore-doc",
+    "Adobe-2006",
+    "Adobe-Display-PostScript",
+    "Adobe-Glyph",
+    "Adobe-Utopia",
+    "ADSL",
+    "AFL-1.1",
+    "AFL-1.2",
+    "AFL-2.0",
+    "AFL-2.1",
+    "AFL-3.0",
+    "Afmparse",
+    "AGPL-1.0-only",
+    "AGPL-1.0-or-later",
+    "AGPL-3.0-only",
+    "AGPL-3.0-or-later",
+    "Aladdin",
+    "AMD-newlib",
+    "AMDPLPA",
+    "AML",
+    "AML-glslang",
+    "AMPAS",
+    "ANTLR-PD",
+    "ANTLR-PD-fallback",
+    "any-OSI",
+    "any-OSI-perl-modules",
+    "Apache-1.0",
+    "Apache-1.1",
+    "Apache-2.0",
+    "APAFML",
+    "APL-1.0",
+    "App-s2p",
+    "APSL-1.0",
+    "APSL-1.1",
+    "APSL-1.2",
+    "APSL-2.0",
+    "Arphic-1999",
+    "Artistic-1.0",
+    "Artistic-1.0-cl8",
+    "Artistic-1.0-Perl",
+    "Artistic-2.0",
+    "Artistic-dist",
+    "Aspell-RU",
+    "ASWF-Digital-Assets-1.0",
+    "ASWF-Digital-Assets-1.1",
+    "Baekmuk",
+    "Bahyph",
+    "Barr",
+    "bcrypt-Solar-Designer",
+    "Beerware",
+    "Bitstream-Charter",
+    "Bitstream-Vera",
+    "BitTorrent-1.0",
+    "BitTorrent-1.1",
+    "blessing",
+    "BlueOak-1.0.0",
+    "Boehm-GC",
+    "Boehm-GC-without-fee",
+    "Borceux",
+    "Brian-Gladman-2-Clause",
+    "Brian-Gladman-3-Clause",
+    "BSD-1-Clause",
+    "BSD-2-Clause",
+    "BSD-2-Clause-Darwin",
+    "BSD-2-Clause-first-lines",
+    "BSD-2-Clause-Patent",
+    "BSD-2-Clause-pkgconf-disclaimer",
+    "BSD-2-Clause-Views",
+    "BSD-3-Clause",
+    "BSD-3-Clause-acpica",
+    "BSD-3-Clause-Attribution",
+    "BSD-3-Clause-Clear",
+    "BSD-3-Clause-flex",
+    "BSD-3-Clause-HP",
+    "BSD-3-Clause-LBNL",
+    "BSD-3-Clause-Modification",
+    "BSD-3-Clause-No-Military-License",
+    "BSD-3-Clause-No-Nuclear-License",
+    "BSD-3-Clause-No-Nuclear-License-2014",
+    "BSD-3-Clause-No-Nuclear-Warranty",
+    "BSD-3-Clause-Open-MPI",
+    "BSD-3-Clause-Sun",
+    "BSD-4-Clause",
+    "BSD-4-Clause-Shortened",
+    "BSD-4-Clause-UC",
+    "BSD-4.3RENO",
+    "BSD-4.3TAHOE",
+    "BSD-Advertising-Acknowledgement",
+    "BSD-Attribution-HPND-disclaimer",
+    "BSD-Inferno-Nettverk",
+    "BSD-Protection",
+    "BSD-Source-beginning-file",
+    "BSD-Source-Code",
+    "BSD-Systemics",
+    "BSD-Systemics-W3Works",
+    "BSL-1.0",
+    "BUSL-1.1",
+    "bzip2-1.0.6",
+    "C-UDA-1.0",
+    "CAL-1.0",
+    "CAL-1.0-Combined-Work-Exception",
+    "Caldera",
+    "Caldera-no-preamble",
+    "Catharon",
+    "CATOSL-1.1",
+    "CC-BY-1.0",
+    "CC-BY-2.0",
+    "CC-BY-2.5",
+    "CC-BY-2.5-AU",
+    "CC-BY-3.0",
+    "CC-BY-3.0-AT",
+    "CC-BY-3.0-AU",
+    "CC-BY-3.0-DE",
+    "CC-BY-3.0-IGO",
+    "CC-BY-3.0-NL",
+    "CC-BY-3.0-US",
+    "CC-BY-4.0",
+    "CC-BY-NC-1.0",
+    "CC-BY-NC-2.0",
+    "CC-BY-NC-2.5",
+    "CC-BY-NC-3.0",
+    "CC-BY-NC-3.0-DE",
+    "CC-BY-NC-4.0",
+    "CC-BY-NC-ND-1.0",
+    "CC-BY-NC-ND-2.0",
+    "CC-BY-NC-ND-2.5",
+    "CC-BY-NC-ND-3.0",
+    "CC-BY-NC-ND-3.0-DE",
+    "CC-BY-NC-ND-3.0-IGO",
+    "CC-BY-NC-ND-4.0",
+    "CC-BY-NC-SA-1.0",
+    "CC-BY-NC-SA-2.0",
+    "CC-BY-NC-SA-2.0-DE",
+    "CC-BY-NC-SA-2.0-FR",
+    "CC-BY-NC-SA-2.0-UK",
+    "CC-BY-NC-SA-2.5",
+    "CC-BY-NC-SA-3.0",
+    "CC-BY-NC-SA-3.0-DE",
+    "CC-BY-NC-SA-3.0-IGO",
+    "CC-BY-NC-SA-4.0",
+    "CC-BY-ND-1.0",
+    "CC-BY-ND-2.0",
+    "CC-BY-ND-2.5",
+    "CC-BY-ND-3.0",
+    "CC-BY-ND-3.0-DE",
+    "CC-BY-ND-4.0",
+    "CC-BY-SA-1.0",
+    "CC-BY-SA-2.0",
+    "CC-BY-SA-2.0-UK",
+    "CC-BY-SA-2.1-JP",
+    "CC-BY-SA-2.5",
+    "CC-BY-SA-3.0",
+    "CC-BY-SA-3.0-AT",
+    "CC-BY-SA-3.0-DE",
+    "CC-BY-SA-3.0-IGO",
+    "CC-BY-SA-4.0",
+    "CC-PDDC",
+    "CC-PDM-1.0",
+    "CC-SA-1.0",
+    "CC0-1.0",
+    "CDDL-1.0",
+    "CDDL-1.1",
+    "CDL-1.0",
+    "CDLA-Permissive-1.0",
+    "CDLA-Permissive-2.0",
+    "CDLA-Sharing-1.0",
+    "CECILL-1.0",
+    "CECILL-1.1",
+    "CECILL-2.0",
+    "CECILL-2.1",
+    "CECILL-B",
+    "CECILL-C",
+    "CERN-OHL-1.1",
+    "CERN-OHL-1.2",
+    "CERN-OHL-P-2.0",
+    "CERN-OHL-S-2.0",
+    "CERN-OHL-W-2.0",
+    "CFITSIO",
+    "check-cvs",
+    "checkmk",
+    "ClArtistic",
+    "Clips",
+    "CMU-Mach",
+    "CMU-Mach-nodoc",
+    "CNRI-Jython",
+    "CNRI-Python",
+    "CNRI-Python-GPL-Compatible",
+    "COIL-1.0",
+    "Community-Spec-1.0",
+    "Condor-1.1",
+    "copyleft-next-0.3.0",
+    "copyleft-next-0.3.1",
+    "Cornell-Lossless-JPEG",
+    "CPAL-1.0",
+    "CPL-1.0",
+    "CPOL-1.02",
+    "Cronyx",
+    "Crossword",
+    "CryptoSwift",
+    "CrystalStacker",
+    "CUA-OPL-1.0",
+    "Cube",
+    "curl",
+    "cve-tou",
+    "D-FSL-1.0",
+    "DEC-3-Clause",
+    "diffmark",
+    "DL-DE-BY-2.0",
+    "DL-DE-ZERO-2.0",
+    "DOC",
+    "DocBook-DTD",
+    "DocBook-Schema",
+    "DocBook-Stylesheet",
+    "DocBook-XML",
+    "Dotseqn",
+    "DRL-1.0",
+    "DRL-1.1",
+    "DSDP",
+    "dtoa",
+    "dvipdfm",
+    "ECL-1.0",
+    "ECL-2.0",
+    "EFL-1.0",
+    "EFL-2.0",
+    "eGenix",
+    "Elastic-2.0",
+    "Entessa",
+    "EPICS",
+    "EPL-1.0",
+    "EPL-2.0",
+    "ErlPL-1.1",
+    "etalab-2.0",
+    "EUDatagrid",
+    "EUPL-1.0",
+    "EUPL-1.1",
+    "EUPL-1.2",
+    "Eurosym",
+    "Fair",
+    "FBM",
+    "FDK-AAC",
+    "Ferguson-Twofish",
+    "Frameworx-1.0",
+    "FreeBSD-DOC",
+    "FreeImage",
+    "FSFAP",
+    "FSFAP-no-warranty-disclaimer",
+    "FSFUL",
+    "FSFULLR",
+    "FSFULLRSD",
+    "FSFULLRWD",
+    "FSL-1.1-ALv2",
+    "FSL-1.1-MIT",
+    "FTL",
+    "Furuseth",
+    "fwlw",
+    "Game-Programming-Gems",
+    "GCR-docs",
+    "GD",
+    "generic-xts",
+    "GFDL-1.1-invariants",
+    "GFDL-1.1-invariants-only",
+    "GFDL-1.1-invariants-or-later",
+    "GFDL-1.1-no-invariants",
+    "GFDL-1.1-no-invariants-only",
+    "GFDL-1.1-no-invariants-or-later",
+    "GFDL-1.1-only",
+    "GFDL-1.1-or-later",
+    "GFDL-1.2-invariants",
+    "GFDL-1.2-invariants-only",
+    "GFDL-1.2-invariants-or-later",
+    "GFDL-1.2-no-invariants",
+    "GFDL-1.2-no-invariants-only",
+    "GFDL-1.2-no-invariants-or-later",
+    "GFDL-1.2-only",
+    "GFDL-1.2-or-later",
+    "GFDL-1.3-invariants",
+    "GFDL-1.3-invariants-only",
+    "GFDL-1.3-invariants-or-later",
+    "GFDL-1.3-no-invariants",
+    "GFDL-1.3-no-invariants-only",
+    "GFDL-1.3-no-invariants-or-later",
+    "GFDL-1.3-only",
+    "GFDL-1.3-or-later",
+    "Giftware",
+    "GL2PS",
+    "Glide",
+    "Glulxe",
+    "GLWTPL",
+    "gnuplot",
+    "GPL-1.0-only",
+    "GPL-1.0-or-later",
+    "GPL-2.0-only",
+    "GPL-2.0-or-later",
+    "GPL-3.0-only",
+    "GPL-3.0-or-later",
+    "Graphics-Gems",
+    "gSOAP-1.3b",
+    "gtkbook",
+    "Gutmann",
+    "HaskellReport",
+    "HDF5",
+    "hdparm",
+    "HIDAPI",
+    "Hippocratic-2.1",
+    "HP-1986",
+    "HP-1989",
+    "HPND",
+    "HPND-DEC",
+    "HPND-doc",
+    "HPND-doc-sell",
+    "HPND-export-US",
+    "HPND-export-US-acknowledgement",
+    "HPND-export-US-modify",
+    "HPND-export2-US",
+    "HPND-Fenneberg-Livingston",
+    "HPND-INRIA-IMAG",
+    "HPND-Intel",
+    "HPND-Kevlin-Henney",
+    "HPND-Markus-Kuhn",
+    "HPND-merchantability-variant",
+    "HPND-MIT-disclaimer",
+    "HPND-Netrek",
+    "HPND-Pbmplus",
+    "HPND-sell-MIT-disclaimer-xserver",
+    "HPND-sell-regexpr",
+    "HPND-sell-variant",
+    "HPND-sell-variant-MIT-disclaimer",
+    "HPND-sell-variant-MIT-disclaimer-rev",
+    "HPND-UC",
+    "HPND-UC-export-US",
+    "HTMLTIDY",
+    "IBM-pibs",
+    "ICU",
+    "IEC-Code-Components-EULA",
+    "IJG",
+    "IJG-short",
+    "ImageMagick",
+    "iMatix",
+    "Imlib2",
+    "Info-ZIP",
+    "Inner-Net-2.0",
+    "InnoSetup",
+    "Intel",
+    "Intel-ACPI",
+    "Interbase-1.0",
+    "IPA",
+    "IPL-1.0",
+    "ISC",
+    "ISC-Veillard",
+    "Jam",
+    "JasPer-2.0",
+    "jove",
+    "JPL-image",
+    "JPNIC",
+    "JSON",
+    "Kastrup",
+    "Kazlib",
+    "Knuth-CTAN",
+    "LAL-1.2",
+    "LAL-1.3",
+    "Latex2e",
+    "Latex2e-translated-notice",
+    "Leptonica",
+    "LGPL-2.0-only",
+    "LGPL-2.0-or-later",
+    "LGPL-2.1-only",
+    "LGPL-2.1-or-later",
+    "LGPL-3.0-only",
+    "LGPL-3.0-or-later",
+    "LGPLLR",
+    "Libpng",
+    "libpng-1.6.35",
+    "libpng-2.0",
+    "libselinux-1.0",
+    "libtiff",
+    "libutil-David-Nugent",
+    "LiLiQ-P-1.1",
+    "LiLiQ-R-1.1",
+    "LiLiQ-Rplus-1.1",
+    "Linux-man-pages-1-para",
+    "Linux-man-pages-copyleft",
+    "Linux-man-pages-copyleft-2-para",
+    "Linux-man-pages-copyleft-var",
+    "Linux-OpenIB",
+    "LOOP",
+    "LPD-document",
+    "LPL-1.0",
+    "LPL-1.02",
+    "LPPL-1.0",
+    "LPPL-1.1",
+    "LPPL-1.2",
+    "LPPL-1.3a",
+    "LPPL-1.3c",
+    "lsof",
+    "Lucida-Bitmap-Fonts",
+    "LZMA-SDK-9.11-to-9.20",
+    "LZMA-SDK-9.22",
+    "Mackerras-3-Clause",
+    "Mackerras-3-Clause-acknowledgment",
+    "magaz",
+    "mailprio",
+    "MakeIndex",
+    "man2html",
+    "Martin-Birgmeier",
+    "McPhee-slideshow",
+    "metamail",
+    "Minpack",
+    "MIPS",
+    "MirOS",
+    "MIT",
+    "MIT-0",
+    "MIT-advertising",
+    "MIT-Click",
+    "MIT-CMU",
+    "MIT-enna",
+    "MIT-feh",
+    "MIT-Festival",
+    "MIT-Khronos-old",
+    "MIT-Modern-Variant",
+    "MIT-open-group",
+    "MIT-testregex",
+    "MIT-Wu",
+    "MITNFA",
+    "MMIXware",
+    "Motosoto",
+    "MPEG-SSG",
+    "mpi-permissive",
+    "mpich2",
+    "MPL-1.0",
+    "MPL-1.1",
+    "MPL-2.0",
+    "MPL-2.0-no-copyleft-exception",
+    "mplus",
+    "MS-LPL",
+    "MS-PL",
+    "MS-RL",
+    "MTLL",
+    "MulanPSL-1.0",
+    "MulanPSL-2.0",
+    "Multics",
+    "Mup",
+    "NAIST-2003",
+    "NASA-1.3",
+    "Naumen",
+    "NBPL-1.0",
+    "NCBI-PD",
+    "NCGL-UK-2.0",
+    "NCL",
+    "NCSA",
+    "Net-SNMP",
+    "NetCDF",
+    "Newsletr",
+    "NGPL",
+    "ngrep",
+    "NICTA-1.0",
+    "NIST-PD",
+    "NIST-PD-fallback",
+    "NIST-Software",
+    "NLOD-1.0",
+    "NLOD-2.0",
+    "NLPL",
+    "NOASSERTION",
+    "Nokia",
+    "NOSL",
+    "Noweb",
+    "NPL-1.0",
+    "NPL-1.1",
+    "NPOSL-3.0",
+    "NRL",
+    "NTIA-PD",
+    "NTP",
+    "NTP-0",
+    "O-UDA-1.0",
+    "OAR",
+    "OCCT-PL",
+    "OCLC-2.0",
+    "ODbL-1.0",
+    "ODC-By-1.0",
+    "OFFIS",
+    "OFL-1.0",
+    "OFL-1.0-no-RFN",
+    "OFL-1.0-RFN",
+    "OFL-1.1",
+    "OFL-1.1-no-RFN",
+    "OFL-1.1-RFN",
+    "OGC-1.0",
+    "OGDL-Taiwan-1.0",
+    "OGL-Canada-2.0",
+    "OGL-UK-1.0",
+    "OGL-UK-2.0",
+    "OGL-UK-3.0",
+    "OGTSL",
+    "OLDAP-1.1",
+    "OLDAP-1.2",
+    "OLDAP-1.3",
+    "OLDAP-1.4",
+    "OLDAP-2.0",
+    "OLDAP-2.0.1",
+    "OLDAP-2.1",
+    "OLDAP-2.2",
+    "OLDAP-2.2.1",
+    "OLDAP-2.2.2",
+    "OLDAP-2.3",
+    "OLDAP-2.4",
+    "OLDAP-2.5",
+    "OLDAP-2.6",
+    "OLDAP-2.7",
+    "OLDAP-2.8",
+    "OLFL-1.3",
+    "OML",
+    "OpenPBS-2.3",
+    "OpenSSL",
+    "OpenSSL-standalone",
+    "OpenVision",
+    "OPL-1.0",
+    "OPL-UK-3.0",
+    "OPUBL-1.0",
+    "OSET-PL-2.1",
+    "OSL-1.0",
+    "OSL-1.1",
+    "OSL-2.0",
+    "OSL-2.1",
+    "OSL-3.0",
+    "PADL",
+    "Parity-6.0.0",
+    "Parity-7.0.0",
+    "PDDL-1.0",
+    "PHP-3.0",
+    "PHP-3.01",
+    "Pixar",
+    "pkgconf",
+    "Plexus",
+    "pnmstitch",
+    "PolyForm-Noncommercial-1.0.0",
+    "PolyForm-Small-Business-1.0.0",
+    "PostgreSQL",
+    "PPL",
+    "PSF-2.0",
+    "psfrag",
+    "psutils",
+    "Python-2.0",
+    "Python-2.0.1",
+    "python-ldap",
+    "Qhull",
+    "QPL-1.0",
+    "QPL-1.0-INRIA-2004",
+    "radvd",
+    "Rdisc",
+    "RHeCos-1.1",
+    "RPL-1.1",
+    "RPL-1.5",
+    "RPSL-1.0",
+    "RSA-MD",
+    "RSCPL",
+    "Ruby",
+    "Ruby-pty",
+    "SAX-PD",
+    "SAX-PD-2.0",
+    "Saxpath",
+    "SCEA",
+    "SchemeReport",
+    "Sendmail",
+    "Sendmail-8.23",
+    "Sendmail-Open-Source-1.1",
+    "SGI-B-1.0",
+    "SGI-B-1.1",
+    "SGI-B-2.0",
+    "SGI-OpenGL",
+    "SGP4",
+    "SHL-0.5",
+    "SHL-0.51",
+    "SimPL-2.0",
+    "SISSL",
+    "SISSL-1.2",
+    "SL",
+    "Sleepycat",
+    "SMAIL-GPL",
+    "SMLNJ",
+    "SMPPL",
+    "SNIA",
+    "snprintf",
+    "SOFA",
+    "softSurfer",
+    "Soundex",
+    "Spencer-86",
+    "Spencer-94",
+    "Spencer-99",
+    "SPL-1.0",
+    "ssh-keyscan",
+    "SSH-OpenSSH",
+    "SSH-short",
+    "SSLeay-standalone",
+    "SSPL-1.0",
+    "SugarCRM-1.1.3",
+    "SUL-1.0",
+    "Sun-PPP",
+    "Sun-PPP-2000",
+    "SunPro",
+    "SWL",
+    "swrule",
+    "Symlinks",
+    "TAPR-OHL-1.0",
+    "TCL",
+    "TCP-wrappers",
+    "TermReadKey",
+    "TGPPL-1.0",
+    "ThirdEye",
+    "threeparttable",
+    "TMate",
+    "TORQUE-1.1",
+    "TOSL",
+    "TPDL",
+    "TPL-1.0",
+    "TrustedQSL",
+    "TTWL",
+    "TTYP0",
+    "TU-Berlin-1.0",
+    "TU-Berlin-2.0",
+    "Ubuntu-font-1.0",
+    "UCAR",
+    "UCL-1.0",
+    "ulem",
+    "UMich-Merit",
+    "Unicode-3.0",
+    "Unicode-DFS-2015",
+    "Unicode-DFS-2016",
+    "Unicode-TOU",
+    "UnixCrypt",
+    "Unlicense",
+    "Unlicense-libtelnet",
+    "Unlicense-libwhirlpool",
+    "UPL-1.0",
+    "URT-RLE",
+    "Vim",
+    "VOSTROM",
+    "VSL-1.0",
+    "W3C",
+    "W3C-19980720",
+    "W3C-20150513",
+    "w3m",
+    "Watcom-1.0",
+    "Widget-Workshop",
+    "Wsuipa",
+    "WTFPL",
+    "wwl",
+    "X11",
+    "X11-distribute-modifications-variant",
+    "X11-swapped",
+    "Xdebug-1.03",
+    "Xerox",
+    "Xfig",
+    "XFree86-1.1",
+    "xinetd",
+    "xkeyboard-config-Zinoviev",
+    "xlock",
+    "Xnet",
+    "xpp",
+    "XSkat",
+    "xzoom",
+    "YPL-1.0",
+    "YPL-1.1",
+    "Zed",
+    "Zeeff",
+    "Zend-2.0",
+    "Zimbra-1.3",
+    "Zimbra-1.4",
+    "Zlib",
+    "zlib-acknowledgement",
+    "ZPL-1.1",
+    "ZPL-2.0",
+    "ZPL-2.1",
+];
+
+/// License exception identifiers of the SPDX license list 3.27.0
+const EXCEPTIONS: &[&str] = &[
+    "389-exception",
+    "Asterisk-exception",
+    "Asterisk-linking-protocols-exception",
+    "Autoconf-exception-2.0",
+    "Autoconf-exception-3.0",
+    "Autoconf-exception-generic",
+    "Autoconf-exception-generic-3.0",
+    "Autoconf-exception-macro",
+    "Bison-exception-1.24",
+    "Bison-exception-2.2",
+    "Bootloader-exception",
+    "CGAL-linking-exception",
+    "Classpath-exception-2.0",
+    "CLISP-exception-2.0",
+    "cryptsetup-OpenSSL-exception",
+    "Digia-Qt-LGPL-exception-1.1",
+    "DigiRule-FOSS-exception",
+    "eCos-exception-2.0",
+    "erlang-otp-linking-exception",
+    "Fawkes-Runtime-exception",
+    "FLTK-exception",
+    "fmt-exception",
+    "Font-exception-2.0",
+    "freertos-exception-2.0",
+    "GCC-exception-2.0",
+    "GCC-exception-2.0-note",
+    "GCC-exception-3.1",
+    "Gmsh-exception",
+    "GNAT-exception",
+    "GNOME-examples-exception",
+    "GNU-compiler-exception",
+    "gnu-javamail-exception",
+    "GPL-3.0-389-ds-base-exception",
+    "GPL-3.0-interface-exception",
+    "GPL-3.0-linking-exception",
+    "GPL-3.0-linking-source-exception",
+    "GPL-CC-1.0",
+    "GStreamer-exception-2005",
+    "GStreamer-exception-2008",
+    "harbour-exception",
+    "i2p-gpl-java-exception",
+    "Independent-modules-exception",
+    "KiCad-libraries-exception",
+    "LGPL-3.0-linking-exception",
+    "libpri-OpenH323-exception",
+    "Libtool-exception",
+    "Linux-syscall-note",
+    "LLGPL",
+    "LLVM-exception",
+    "LZMA-exception",
+    "mif-exception",
+    "mxml-exception",
+    "Nokia-Qt-exception-1.1",
+    "OCaml-LGPL-linking-exception",
+    "OCCT-exception-1.0",
+    "OpenJDK-assembly-exception-1.0",
+    "openvpn-openssl-exception",
+    "PCRE2-exception",
+    "polyparse-exception",
+    "PS-or-PDF-font-exception-20170817",
+    "QPL-1.0-INRIA-2004-exception",
+    "Qt-GPL-exception-1.0",
+    "Qt-LGPL-exception-1.1",
+    "Qwt-exception-1.0",
+    "romic-exception",
+    "RRDtool-FLOSS-exception-2.0",
+    "SANE-exception",
+    "SHL-2.0",
+    "SHL-2.1",
+    "stunnel-exception",
+    "SWI-exception",
+    "Swift-exception",
+    "Texinfo-exception",
+    "u-boot-exception-2.0",
+    "UBDL-exception",
+    "Universal-FOSS-exception-1.0",
+    "vsftpd-openssl-exception",
+    "WxWindows-exception-3.1",
+    "x11vnc-openssl-exception",
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn normalize(input: &str) -> String {
+        parse(input).unwrap().expression.normalize().to_string()
+    }
+
+    #[test]
+    fn normalize_ok() {
+        assert_eq!(normalize("Apache-2.0/MIT"), "MIT OR Apache-2.0");
+        assert_eq!(normalize("MIT OR Apache-2.0"), "MIT OR Apache-2.0");
+        assert_eq!(normalize("mit or apache-2.0"), "MIT OR Apache-2.0");
+        assert_eq!(normalize("MIT/Apache-2.0/MIT"), "MIT OR Apache-2.0");
+        assert_eq!(normalize("(MIT OR Zlib) OR ISC"), "MIT OR ISC OR Zlib");
+        assert_eq!(
+            normalize("Apache-2.0 WITH LLVM-exception AND (Zlib OR MIT)"),
+            "Apache-2.0 WITH LLVM-exception AND (MIT OR Zlib)"
+        );
+        assert_eq!(normalize("GPL-2.0+"), "GPL-2.0-or-later");
+        assert_eq!(normalize("LicenseRef-Foo"), "LicenseRef-Foo");
+        assert_eq!(
+            normalize("GPL-2.0-with-classpath-exception"),
+            "GPL-2.0-only WITH Classpath-exception-2.0"
+        );
+    }
+
+    #[test]
+    fn issues_ok() {
+        assert!(parse("MIT OR Apache-2.0").unwrap().issues.is_empty());
+        assert_eq!(
+            parse("Apache-2.0/MIT").unwrap().issues,
+            vec![Issue::DeprecatedSyntax {
+                found: "/".to_owned(),
+                replacement: "OR".to_owned()
+            }]
+        );
+        // Ids that were missing from a hand-picked subset before
+        assert!(parse("Unicode-3.0 OR BlueOak-1.0.0 OR MIT-0")
+            .unwrap()
+            .issues
+            .is_empty());
+        assert!(parse("CDLA-Permissive-2.0 WITH LLVM-exception")
+            .unwrap()
+            .issues
+            .is_empty());
+        assert_eq!(
+            parse("MIT OR Foo-1.0").unwrap().issues,
+            vec![Issue::UnknownLicense("Foo-1.0".to_owned())]
+        );
+        assert_eq!(
+            parse("GPL-3.0").unwrap().issues,
+            vec![Issue::DeprecatedLicense {
+                id: "GPL-3.0".to_owned(),
+                replacement: "GPL-3.0-only".to_owned()
+            }]
+        );
+    }
+
+    #[test]
+    fn satisfies_ok() {
+        let allowed = |id: &str| ["MIT", "Apache-2.0"].contains(&id);
+        let satisfies = |input: &str| parse(input).unwrap().expression.satisfies(&allowed);
+
+        assert!(satisfies("MIT"));
+        assert!(satisfies("MIT OR GPL-3.0"));
+        assert!(satisfies("MIT AND Apache-2.0"));
+        assert!(!satisfies("MIT AND GPL-3.0"));
+        assert!(!satisfies("(MIT OR GPL-3.0) AND LGPL-2.1"));
+    }
+
+    #[test]
+    fn parse_err() {
+        assert!(parse("MIT OR").is_err());
+        assert!(parse("(MIT").is_err());
+        assert!(parse("MIT Apache-2.0").is_err());
+        assert!(parse("MIT WITH").is_err());
+    }
+}
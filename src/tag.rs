@@ -0,0 +1,113 @@
+use std::{error::Error, fmt};
+
+use cargo_toml::Manifest;
+
+#[derive(Debug)]
+pub struct TagMismatch {
+    pub tag: String,
+    pub name: String,
+    pub tagged: String,
+    pub version: String,
+}
+
+impl fmt::Display for TagMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Tag `{}` is version {} but {} is at version {}",
+            self.tag, self.tagged, self.name, self.version
+        )
+    }
+}
+
+impl Error for TagMismatch {}
+
+/// The tag of a `refs/tags/` ref in `GITHUB_REF`, as set by tag triggered workflows
+pub fn from_env() -> Result<String, Box<dyn Error>> {
+    let git_ref =
+        std::env::var("GITHUB_REF").map_err(|_| "No tag given and GITHUB_REF is not set")?;
+    match git_ref.strip_prefix("refs/tags/") {
+        Some(tag) => Ok(tag.to_owned()),
+        None => Err(format!("GITHUB_REF `{git_ref}` is not a tag").into()),
+    }
+}
+
+/// Find the package the tag belongs to and make sure the versions are equal. Patterns
+/// without `{name}` must be checked against a single package. Returns the package name.
+pub fn check(tag: &str, pattern: &str, packages: &[Manifest]) -> Result<String, Box<dyn Error>> {
+    let tag = tag.strip_prefix("refs/tags/").unwrap_or(tag);
+    if !pattern.contains("{version}") {
+        return Err(format!("Tag pattern `{pattern}` has no `{{version}}` placeholder").into());
+    }
+
+    let mut candidates: Vec<_> = packages.iter().filter_map(|m| m.package.as_ref()).collect();
+    // Prefer `foo-bar` over `foo` when both match `{name}-...`
+    candidates.sort_by_key(|package| std::cmp::Reverse(package.name().len()));
+
+    let (package, tagged) = candidates
+        .into_iter()
+        .find_map(|package| {
+            let pattern = pattern.replace("{name}", package.name());
+            let (prefix, suffix) = pattern.split_once("{version}")?;
+            let tagged = tag.strip_prefix(prefix)?.strip_suffix(suffix)?;
+            Some((package, tagged))
+        })
+        .ok_or_else(|| format!("Tag `{tag}` does not match the pattern `{pattern}`"))?;
+
+    let mismatch = || TagMismatch {
+        tag: tag.to_owned(),
+        name: package.name().to_owned(),
+        tagged: tagged.to_owned(),
+        version: package.version().to_owned(),
+    };
+    let tagged: semver::Version = tagged
+        .parse()
+        .map_err(|_| format!("Tag `{tag}` has no valid semver version `{tagged}`"))?;
+    let version: semver::Version = package.version().parse().map_err(|_| mismatch())?;
+
+    match tagged == version {
+        true => Ok(package.name().to_owned()),
+        false => Err(mismatch().into()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn manifest(name: &str, version: &str) -> Manifest {
+        Manifest::from_str(&format!(
+            "[package]\nname = \"{name}\"\nversion = \"{version}\""
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn check_ok() {
+        let packages = [manifest("foo", "1.4.0"), manifest("foo-bar", "0.2.0-rc.1")];
+
+        assert_eq!(
+            check("v1.4.0", "v{version}", &packages[..1]).unwrap(),
+            "foo"
+        );
+        assert_eq!(
+            check("refs/tags/foo-v1.4.0", "{name}-v{version}", &packages).unwrap(),
+            "foo"
+        );
+        assert_eq!(
+            check("foo-bar-v0.2.0-rc.1", "{name}-v{version}", &packages).unwrap(),
+            "foo-bar"
+        );
+    }
+
+    #[test]
+    fn check_err() {
+        let packages = [manifest("foo", "1.4.0")];
+
+        assert!(check("v1.3.0", "v{version}", &packages).is_err());
+        assert!(check("1.4.0", "v{version}", &packages).is_err());
+        assert!(check("v1.4", "v{version}", &packages).is_err());
+        assert!(check("bar-v1.4.0", "{name}-v{version}", &packages).is_err());
+        assert!(check("v1.4.0", "v", &packages).is_err());
+    }
+}
@@ -0,0 +1,164 @@
+use std::fmt;
+
+/// Built-in properties of a target triple
+#[derive(Debug, PartialEq, Clone)]
+pub struct Target {
+    pub triple: &'static str,
+    pub arch: &'static str,
+    pub os: &'static str,
+    pub env: &'static str,
+    pub families: Vec<&'static str>,
+}
+
+#[derive(Debug)]
+pub struct UnknownTarget(pub String);
+
+impl fmt::Display for UnknownTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown target triple `{}`", self.0)
+    }
+}
+
+impl std::error::Error for UnknownTarget {}
+
+impl std::str::FromStr for Target {
+    type Err = UnknownTarget;
+
+    fn from_str(triple: &str) -> Result<Self, Self::Err> {
+        TARGETS
+            .iter()
+            .find(|(t, ..)| *t == triple)
+            .map(|&(triple, arch, os, env, families)| Self {
+                triple,
+                arch,
+                os,
+                env,
+                families: families.split(',').filter(|f| !f.is_empty()).collect(),
+            })
+            .ok_or_else(|| UnknownTarget(triple.to_owned()))
+    }
+}
+
+impl Target {
+    pub fn is_windows(&self) -> bool {
+        self.families.contains(&"windows")
+    }
+
+    pub fn is_apple(&self) -> bool {
+        matches!(self.os, "macos" | "ios" | "tvos" | "watchos" | "visionos")
+    }
+
+    pub fn is_wasm(&self) -> bool {
+        self.arch.starts_with("wasm")
+    }
+
+    pub fn pointer_width(&self) -> &'static str {
+        match self.arch {
+            "x86_64" | "aarch64" | "loongarch64" | "powerpc64" | "riscv64" | "s390x" => "64",
+            "avr" | "msp430" => "16",
+            _ => "32",
+        }
+    }
+
+    pub fn endian(&self) -> &'static str {
+        match self.triple.split('-').next() {
+            Some("powerpc64" | "s390x") => "big",
+            _ => "little",
+        }
+    }
+
+    /// The vendor component of the triple, `unknown` when the triple omits it
+    pub fn vendor(&self) -> &'static str {
+        match self.triple.split('-').nth(1) {
+            Some(vendor @ ("pc" | "apple" | "unknown")) => vendor,
+            _ => "unknown",
+        }
+    }
+}
+
+/// triple, target_arch, target_os, target_env, target_family (comma separated)
+#[rustfmt::skip]
+const TARGETS: &[(&str, &str, &str, &str, &str)] = &[
+    // Linux
+    ("x86_64-unknown-linux-gnu",       "x86_64",      "linux",      "gnu",  "unix"),
+    ("x86_64-unknown-linux-musl",      "x86_64",      "linux",      "musl", "unix"),
+    ("i686-unknown-linux-gnu",         "x86",         "linux",      "gnu",  "unix"),
+    ("i686-unknown-linux-musl",        "x86",         "linux",      "musl", "unix"),
+    ("aarch64-unknown-linux-gnu",      "aarch64",     "linux",      "gnu",  "unix"),
+    ("aarch64-unknown-linux-musl",     "aarch64",     "linux",      "musl", "unix"),
+    ("arm-unknown-linux-gnueabi",      "arm",         "linux",      "gnu",  "unix"),
+    ("arm-unknown-linux-gnueabihf",    "arm",         "linux",      "gnu",  "unix"),
+    ("armv7-unknown-linux-gnueabihf",  "arm",         "linux",      "gnu",  "unix"),
+    ("armv7-unknown-linux-musleabihf", "arm",         "linux",      "musl", "unix"),
+    ("loongarch64-unknown-linux-gnu",  "loongarch64", "linux",      "gnu",  "unix"),
+    ("powerpc64-unknown-linux-gnu",    "powerpc64",   "linux",      "gnu",  "unix"),
+    ("powerpc64le-unknown-linux-gnu",  "powerpc64",   "linux",      "gnu",  "unix"),
+    ("riscv64gc-unknown-linux-gnu",    "riscv64",     "linux",      "gnu",  "unix"),
+    ("s390x-unknown-linux-gnu",        "s390x",       "linux",      "gnu",  "unix"),
+    // Android
+    ("aarch64-linux-android",          "aarch64",     "android",    "",     "unix"),
+    ("armv7-linux-androideabi",        "arm",         "android",    "",     "unix"),
+    ("i686-linux-android",             "x86",         "android",    "",     "unix"),
+    ("x86_64-linux-android",           "x86_64",      "android",    "",     "unix"),
+    // Windows
+    ("x86_64-pc-windows-msvc",         "x86_64",      "windows",    "msvc", "windows"),
+    ("x86_64-pc-windows-gnu",          "x86_64",      "windows",    "gnu",  "windows"),
+    ("x86_64-pc-windows-gnullvm",      "x86_64",      "windows",    "gnu",  "windows"),
+    ("i686-pc-windows-msvc",           "x86",         "windows",    "msvc", "windows"),
+    ("i686-pc-windows-gnu",            "x86",         "windows",    "gnu",  "windows"),
+    ("aarch64-pc-windows-msvc",        "aarch64",     "windows",    "msvc", "windows"),
+    ("aarch64-pc-windows-gnullvm",     "aarch64",     "windows",    "gnu",  "windows"),
+    // Apple
+    ("x86_64-apple-darwin",            "x86_64",      "macos",      "",     "unix"),
+    ("aarch64-apple-darwin",           "aarch64",     "macos",      "",     "unix"),
+    ("aarch64-apple-ios",              "aarch64",     "ios",        "",     "unix"),
+    ("aarch64-apple-ios-sim",          "aarch64",     "ios",        "sim",  "unix"),
+    ("x86_64-apple-ios",               "x86_64",      "ios",        "sim",  "unix"),
+    // BSD and others
+    ("x86_64-unknown-freebsd",         "x86_64",      "freebsd",    "",     "unix"),
+    ("aarch64-unknown-freebsd",        "aarch64",     "freebsd",    "",     "unix"),
+    ("x86_64-unknown-netbsd",          "x86_64",      "netbsd",     "",     "unix"),
+    ("x86_64-unknown-openbsd",         "x86_64",      "openbsd",    "",     "unix"),
+    ("x86_64-unknown-illumos",         "x86_64",      "illumos",    "",     "unix"),
+    // WebAssembly
+    ("wasm32-unknown-unknown",         "wasm32",      "unknown",    "",     "wasm"),
+    ("wasm32-unknown-emscripten",      "wasm32",      "emscripten", "",     "unix,wasm"),
+    ("wasm32-wasip1",                  "wasm32",      "wasi",       "p1",   "wasm"),
+    ("wasm32-wasip2",                  "wasm32",      "wasi",       "p2",   "wasm"),
+    // Bare metal
+    ("aarch64-unknown-none",           "aarch64",     "none",       "",     ""),
+    ("riscv32imac-unknown-none-elf",   "riscv32",     "none",       "",     ""),
+    ("thumbv6m-none-eabi",             "arm",         "none",       "",     ""),
+    ("thumbv7em-none-eabihf",          "arm",         "none",       "",     ""),
+    ("x86_64-unknown-none",            "x86_64",      "none",       "",     ""),
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_ok() {
+        let target: Target = "x86_64-pc-windows-msvc".parse().unwrap();
+        assert_eq!(target.os, "windows");
+        assert_eq!(target.env, "msvc");
+        assert!(target.is_windows());
+
+        let target: Target = "wasm32-unknown-emscripten".parse().unwrap();
+        assert_eq!(target.families, vec!["unix", "wasm"]);
+        assert!(target.is_wasm());
+
+        let target: Target = "thumbv7em-none-eabihf".parse().unwrap();
+        assert!(target.families.is_empty());
+        assert_eq!(target.pointer_width(), "32");
+
+        let target: Target = "powerpc64le-unknown-linux-gnu".parse().unwrap();
+        assert_eq!(target.endian(), "little");
+        assert_eq!(target.pointer_width(), "64");
+    }
+
+    #[test]
+    fn parse_err() {
+        assert!("x86_64-unknown-nowhere".parse::<Target>().is_err());
+    }
+}
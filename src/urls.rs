@@ -0,0 +1,225 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Forge {
+    GitHub,
+    GitLab,
+    Codeberg,
+    Generic,
+}
+
+impl fmt::Display for Forge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::GitHub => "github",
+            Self::GitLab => "gitlab",
+            Self::Codeberg => "codeberg",
+            Self::Generic => "generic",
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidRepository(pub String);
+
+impl fmt::Display for InvalidRepository {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cannot derive URLs from repository `{}`", self.0)
+    }
+}
+
+impl std::error::Error for InvalidRepository {}
+
+#[derive(Debug)]
+pub struct UnsupportedForge(pub &'static str);
+
+impl fmt::Display for UnsupportedForge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cannot derive {} for a generic git host", self.0)
+    }
+}
+
+impl std::error::Error for UnsupportedForge {}
+
+/// A `package.repository` value split into its parts
+#[derive(Debug, PartialEq, Clone)]
+pub struct Repository {
+    pub forge: Forge,
+    pub host: String,
+    pub slug: String,
+}
+
+impl Repository {
+    /// Accepts https, ssh and scp-like (`git@host:owner/repo.git`) URLs
+    pub fn parse(url: &str) -> Result<Self, InvalidRepository> {
+        let invalid = || InvalidRepository(url.to_owned());
+        let trimmed = url.trim();
+
+        let (scheme, rest) = match trimmed.split_once("://") {
+            Some((scheme, rest)) => (Some(scheme.to_lowercase()), rest.to_owned()),
+            None => (None, trimmed.replacen(':', "/", 1)),
+        };
+
+        let rest = rest.rsplit_once('@').map_or(rest.as_str(), |(_, r)| r);
+        let (authority, path) = rest.split_once('/').ok_or_else(invalid)?;
+        let authority = authority.to_lowercase();
+        let hostname = authority.split(':').next().unwrap_or(&authority).to_owned();
+        // The web server may listen on another port, the port of an ssh remote is no use there
+        let host = match scheme.as_deref() {
+            Some("http" | "https") => authority,
+            _ => hostname.clone(),
+        };
+
+        let path = path.trim_end_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+
+        if host.is_empty() || !path.contains('/') {
+            return Err(invalid());
+        }
+
+        let forge = match hostname.as_str() {
+            "github.com" | "www.github.com" => Forge::GitHub,
+            "codeberg.org" => Forge::Codeberg,
+            h if h == "gitlab.com" || h.starts_with("gitlab.") => Forge::GitLab,
+            _ => Forge::Generic,
+        };
+
+        // Only GitLab allows nested groups, other forges use the first two segments. GitLab
+        // pages below a project like `/-/tree/main` are separated with `/-/`
+        let slug = match forge {
+            Forge::GitLab | Forge::Generic => path.split("/-/").next().unwrap_or(path).to_owned(),
+            _ => path.splitn(3, '/').take(2).collect::<Vec<_>>().join("/"),
+        };
+
+        Ok(Self { forge, host, slug })
+    }
+
+    pub fn web_url(&self) -> String {
+        format!("https://{}/{}", self.host, self.slug)
+    }
+
+    pub fn tag_url(&self, tag: &str) -> Result<String, UnsupportedForge> {
+        let web = self.web_url();
+        match self.forge {
+            Forge::GitHub | Forge::Codeberg => Ok(format!("{web}/releases/tag/{tag}")),
+            Forge::GitLab => Ok(format!("{web}/-/tags/{tag}")),
+            Forge::Generic => Err(UnsupportedForge("a tag URL")),
+        }
+    }
+
+    /// The source tarball the forge generates for a tag
+    pub fn archive_url(&self, tag: &str) -> Result<String, UnsupportedForge> {
+        let web = self.web_url();
+        let repo = self.slug.rsplit('/').next().unwrap_or(&self.slug);
+        match self.forge {
+            Forge::GitHub => Ok(format!("{web}/archive/refs/tags/{tag}.tar.gz")),
+            Forge::GitLab => Ok(format!("{web}/-/archive/{tag}/{repo}-{tag}.tar.gz")),
+            Forge::Codeberg => Ok(format!("{web}/archive/{tag}.tar.gz")),
+            Forge::Generic => Err(UnsupportedForge("an archive URL")),
+        }
+    }
+
+    pub fn raw_url(&self, git_ref: &str, file: &str) -> Result<String, UnsupportedForge> {
+        let web = self.web_url();
+        let file = file.trim_start_matches('/');
+        match self.forge {
+            Forge::GitHub => Ok(format!(
+                "https://raw.githubusercontent.com/{}/{git_ref}/{file}",
+                self.slug
+            )),
+            Forge::GitLab => Ok(format!("{web}/-/raw/{git_ref}/{file}")),
+            Forge::Codeberg => Ok(format!("{web}/raw/tag/{git_ref}/{file}")),
+            Forge::Generic => Err(UnsupportedForge("a raw file URL")),
+        }
+    }
+}
+
+/// Expand `{name}` and `{version}` in a tag pattern
+pub fn render_tag(pattern: &str, name: &str, version: &str) -> String {
+    pattern
+        .replace("{name}", name)
+        .replace("{version}", version)
+}
+
+pub fn docs_rs(name: &str, version: &str) -> String {
+    format!("https://docs.rs/{name}/{version}")
+}
+
+pub fn crates_io(name: &str, version: &str) -> String {
+    format!("https://crates.io/crates/{name}/{version}")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_ok() {
+        let expected = Repository {
+            forge: Forge::GitHub,
+            host: "github.com".to_owned(),
+            slug: "nicolaiunrein/cargo-get".to_owned(),
+        };
+
+        for url in [
+            "https://github.com/nicolaiunrein/cargo-get",
+            "https://github.com/nicolaiunrein/cargo-get/",
+            "https://github.com/nicolaiunrein/cargo-get.git",
+            "https://github.com/nicolaiunrein/cargo-get/tree/master",
+            "git@github.com:nicolaiunrein/cargo-get.git",
+            "ssh://git@github.com/nicolaiunrein/cargo-get.git",
+        ] {
+            assert_eq!(Repository::parse(url).unwrap(), expected, "{url}");
+        }
+
+        let gitlab = Repository::parse("https://gitlab.com/crates.rs/sub/cargo_toml").unwrap();
+        assert_eq!(gitlab.forge, Forge::GitLab);
+        assert_eq!(gitlab.slug, "crates.rs/sub/cargo_toml");
+
+        let gitlab = Repository::parse("https://gitlab.com/o/r/-/tree/main").unwrap();
+        assert_eq!(gitlab.slug, "o/r");
+        assert_eq!(
+            gitlab.archive_url("v1.0.0").unwrap(),
+            "https://gitlab.com/o/r/-/archive/v1.0.0/r-v1.0.0.tar.gz"
+        );
+
+        let codeberg = Repository::parse("https://codeberg.org/owner/repo.git").unwrap();
+        assert_eq!(codeberg.forge, Forge::Codeberg);
+
+        let generic = Repository::parse("https://git.example.com:8443/owner/repo").unwrap();
+        assert_eq!(generic.forge, Forge::Generic);
+        assert_eq!(generic.web_url(), "https://git.example.com:8443/owner/repo");
+
+        let ssh = Repository::parse("ssh://git@gitlab.example.com:2222/owner/repo.git").unwrap();
+        assert_eq!(ssh.forge, Forge::GitLab);
+        assert_eq!(ssh.web_url(), "https://gitlab.example.com/owner/repo");
+    }
+
+    #[test]
+    fn parse_err() {
+        assert!(Repository::parse("").is_err());
+        assert!(Repository::parse("https://github.com").is_err());
+        assert!(Repository::parse("https://github.com/owner").is_err());
+    }
+
+    #[test]
+    fn urls_ok() {
+        let repo = Repository::parse("https://gitlab.com/owner/repo").unwrap();
+        assert_eq!(
+            repo.tag_url("v1.0.0").unwrap(),
+            "https://gitlab.com/owner/repo/-/tags/v1.0.0"
+        );
+        assert_eq!(
+            repo.raw_url("v1.0.0", "README.md").unwrap(),
+            "https://gitlab.com/owner/repo/-/raw/v1.0.0/README.md"
+        );
+        assert_eq!(
+            repo.archive_url("v1.0.0").unwrap(),
+            "https://gitlab.com/owner/repo/-/archive/v1.0.0/repo-v1.0.0.tar.gz"
+        );
+        assert_eq!(
+            render_tag("{name}-v{version}", "foo", "1.0.0"),
+            "foo-v1.0.0"
+        );
+    }
+}
@@ -0,0 +1,351 @@
+use std::{
+    error::Error,
+    fmt,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use toml_edit::{DocumentMut, Item};
+
+#[derive(Debug)]
+pub struct OutOfSync(pub Vec<String>);
+
+impl fmt::Display for OutOfSync {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Versions out of sync with package.version:")?;
+        for file in &self.0 {
+            write!(f, "\n  {file}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for OutOfSync {}
+
+/// The package managers whose manifests are kept in sync
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Ecosystem {
+    /// `package.json`, versions are semver
+    Npm,
+    /// `pyproject.toml`, versions are PEP 440
+    Python,
+}
+
+/// A version declared in a manifest of another package manager
+#[derive(Debug)]
+pub struct ForeignVersion {
+    pub path: PathBuf,
+    pub ecosystem: Ecosystem,
+    /// `None` when the version is `dynamic` and taken from `Cargo.toml` by the build backend
+    pub version: Option<String>,
+    content: String,
+}
+
+impl ForeignVersion {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let ecosystem = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Ecosystem::Npm,
+            Some("toml") => Ecosystem::Python,
+            _ => {
+                return Err(format!(
+                    "Unsupported file {}, expected a package.json or pyproject.toml",
+                    path.display()
+                )
+                .into())
+            }
+        };
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read {}: {err}", path.display()))?;
+
+        let version = match ecosystem {
+            Ecosystem::Npm => json_version(&content).map(|span| content[span].to_owned()),
+            Ecosystem::Python => pyproject_version(&content, path)?,
+        };
+        if version.is_none() && !is_dynamic(ecosystem, &content) {
+            return Err(format!("No version in {}", path.display()).into());
+        }
+
+        Ok(Self {
+            path: path.to_owned(),
+            ecosystem,
+            version,
+            content,
+        })
+    }
+
+    /// `package.version` written the way this ecosystem spells it
+    pub fn expected(&self, version: &semver::Version) -> Result<String, Box<dyn Error>> {
+        match self.ecosystem {
+            Ecosystem::Npm => Ok(version.to_string()),
+            Ecosystem::Python => pep440(&version.to_string())
+                .ok_or_else(|| format!("Cannot translate version {version} to PEP 440").into()),
+        }
+    }
+
+    pub fn is_in_sync(&self, version: &semver::Version) -> Result<bool, Box<dyn Error>> {
+        let Some(found) = &self.version else {
+            return Ok(true);
+        };
+        Ok(match self.ecosystem {
+            Ecosystem::Npm => semver::Version::parse(found).ok().as_ref() == Some(version),
+            Ecosystem::Python => pep440(found) == Some(self.expected(version)?),
+        })
+    }
+
+    /// Replace the version and leave everything else as it is
+    pub fn fix(&self, expected: &str) -> Result<(), Box<dyn Error>> {
+        let content = match self.ecosystem {
+            Ecosystem::Npm => {
+                let span = json_version(&self.content).ok_or("No version in package.json")?;
+                let mut content = self.content.clone();
+                content.replace_range(span, expected);
+                content
+            }
+            Ecosystem::Python => {
+                let mut doc: DocumentMut = self.content.parse()?;
+                let item = pyproject_item(&mut doc).ok_or("No version in pyproject.toml")?;
+                let decor = item.as_value().map(|value| value.decor().clone());
+                *item = toml_edit::value(expected);
+                if let (Some(decor), Some(value)) = (decor, item.as_value_mut()) {
+                    *value.decor_mut() = decor;
+                }
+                doc.to_string()
+            }
+        };
+        std::fs::write(&self.path, content)
+            .map_err(|err| format!("Failed to write {}: {err}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+/// Compare every file with `version` and rewrite the ones that differ when `fix` is set.
+/// Returns the fixed files.
+pub fn sync(
+    files: &[PathBuf],
+    version: &semver::Version,
+    fix: bool,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut out_of_sync = Vec::new();
+    let mut fixed = Vec::new();
+
+    for path in files {
+        let foreign = ForeignVersion::load(path)?;
+        if foreign.is_in_sync(version)? {
+            continue;
+        }
+
+        let expected = foreign.expected(version)?;
+        let found = foreign.version.as_deref().unwrap_or_default();
+        match fix {
+            true => {
+                foreign.fix(&expected)?;
+                fixed.push(format!("{}: {found} -> {expected}", path.display()));
+            }
+            false => out_of_sync.push(format!("{}: {found}, expected {expected}", path.display())),
+        }
+    }
+
+    match out_of_sync.is_empty() {
+        true => Ok(fixed),
+        false => Err(OutOfSync(out_of_sync).into()),
+    }
+}
+
+/// `[project].version` or `[tool.poetry].version`
+fn pyproject_version(content: &str, path: &Path) -> Result<Option<String>, Box<dyn Error>> {
+    let mut doc: DocumentMut = content
+        .parse()
+        .map_err(|err| format!("Failed to parse {}: {err}", path.display()))?;
+    Ok(pyproject_item(&mut doc)
+        .and_then(|item| item.as_str())
+        .map(str::to_owned))
+}
+
+fn pyproject_item(doc: &mut DocumentMut) -> Option<&mut Item> {
+    if doc
+        .get("project")
+        .and_then(|project| project.get("version"))
+        .is_some()
+    {
+        return doc.get_mut("project")?.get_mut("version");
+    }
+    doc.get_mut("tool")?.get_mut("poetry")?.get_mut("version")
+}
+
+/// maturin fills in `[project].version` when it is listed in `dynamic`
+fn is_dynamic(ecosystem: Ecosystem, content: &str) -> bool {
+    let Ok(doc) = content.parse::<DocumentMut>() else {
+        return false;
+    };
+    ecosystem == Ecosystem::Python
+        && doc
+            .get("project")
+            .and_then(|project| project.get("dynamic"))
+            .and_then(Item::as_array)
+            .is_some_and(|dynamic| dynamic.iter().any(|v| v.as_str() == Some("version")))
+}
+
+/// Byte range of the top level `"version"` string value, without the quotes
+fn json_version(content: &str) -> Option<Range<usize>> {
+    let bytes = content.as_bytes();
+    let mut depth = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => depth -= 1,
+            b'"' => {
+                let key = string_end(bytes, i)?;
+                let mut next = skip_whitespace(bytes, key + 1);
+                if depth == 1 && &bytes[i + 1..key] == b"version" && bytes.get(next) == Some(&b':')
+                {
+                    next = skip_whitespace(bytes, next + 1);
+                    if bytes.get(next) != Some(&b'"') {
+                        return None;
+                    }
+                    return Some(next + 1..string_end(bytes, next)?);
+                }
+                i = key;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    None
+}
+
+/// Index of the closing quote of the string starting at `start`
+fn string_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'"' => return Some(i),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+fn skip_whitespace(bytes: &[u8], mut i: usize) -> usize {
+    while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+        i += 1;
+    }
+    i
+}
+
+/// The normalized PEP 440 form of a version. Also accepts semver, so `1.0.0-rc.1` becomes
+/// `1.0.0rc1`. `None` if the version has parts PEP 440 cannot express.
+pub fn pep440(version: &str) -> Option<String> {
+    let version = version.trim().to_lowercase();
+    let version = version.strip_prefix('v').unwrap_or(&version);
+    let (version, local) = match version.split_once('+') {
+        Some((version, local)) => (version, Some(local)),
+        None => (version, None),
+    };
+
+    let release_end = version
+        .char_indices()
+        .find(|(i, c)| {
+            !(c.is_ascii_digit()
+                || *c == '.' && version[i + 1..].starts_with(|c: char| c.is_ascii_digit()))
+        })
+        .map_or(version.len(), |(i, _)| i);
+    let (release, mut rest) = version.split_at(release_end);
+    if release.is_empty() {
+        return None;
+    }
+
+    let mut normalized = release.to_owned();
+
+    let pre = [
+        ("alpha", "a"),
+        ("a", "a"),
+        ("beta", "b"),
+        ("b", "b"),
+        ("preview", "rc"),
+        ("pre", "rc"),
+        ("rc", "rc"),
+        ("c", "rc"),
+    ];
+    if let Some((tag, number, remaining)) = segment(rest, &pre) {
+        normalized.push_str(&format!("{tag}{number}"));
+        rest = remaining;
+    }
+    if let Some((_, number, remaining)) =
+        segment(rest, &[("post", "post"), ("rev", "post"), ("r", "post")])
+    {
+        normalized.push_str(&format!(".post{number}"));
+        rest = remaining;
+    }
+    if let Some((_, number, remaining)) = segment(rest, &[("dev", "dev")]) {
+        normalized.push_str(&format!(".dev{number}"));
+        rest = remaining;
+    }
+    if !rest.is_empty() {
+        return None;
+    }
+
+    if let Some(local) = local {
+        let local = local.replace(['-', '_'], ".");
+        if local.is_empty() || !local.chars().all(|c| c.is_ascii_alphanumeric() || c == '.') {
+            return None;
+        }
+        normalized.push_str(&format!("+{local}"));
+    }
+
+    Some(normalized)
+}
+
+/// An optional separator, one of the `tags`, an optional separator and an optional number
+fn segment<'a>(input: &'a str, tags: &[(&str, &'a str)]) -> Option<(&'a str, u64, &'a str)> {
+    let input = input.strip_prefix(['.', '-', '_']).unwrap_or(input);
+    let (tag, rest) = tags
+        .iter()
+        .find_map(|(spelling, tag)| Some((*tag, input.strip_prefix(spelling)?)))?;
+
+    let rest = rest.strip_prefix(['.', '-', '_']).unwrap_or(rest);
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let number = match digits {
+        0 => 0,
+        _ => rest[..digits].parse().ok()?,
+    };
+    Some((tag, number, &rest[digits..]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pep440_ok() {
+        assert_eq!(pep440("1.0.0").as_deref(), Some("1.0.0"));
+        assert_eq!(pep440("1.0.0-rc.1").as_deref(), Some("1.0.0rc1"));
+        assert_eq!(pep440("1.0.0-alpha.2").as_deref(), Some("1.0.0a2"));
+        assert_eq!(pep440("1.0.0-beta").as_deref(), Some("1.0.0b0"));
+        assert_eq!(pep440("1.0.0-dev.3").as_deref(), Some("1.0.0.dev3"));
+        assert_eq!(pep440("1.0.0+build.5").as_deref(), Some("1.0.0+build.5"));
+        assert_eq!(pep440("1.0.0RC1").as_deref(), Some("1.0.0rc1"));
+        assert_eq!(
+            pep440("1.0.0.post1.dev2").as_deref(),
+            Some("1.0.0.post1.dev2")
+        );
+        assert_eq!(pep440("1.0.0-nightly.1"), None);
+        assert_eq!(pep440("latest"), None);
+    }
+
+    #[test]
+    fn json_version_ok() {
+        let content = r#"{
+  "name": "pkg",
+  "dependencies": { "version": "1.0.0" },
+  "description": "\"version\": 2",
+  "version": "0.3.0"
+}"#;
+        assert_eq!(&content[json_version(content).unwrap()], "0.3.0");
+        assert_eq!(json_version(r#"{"name": "pkg"}"#), None);
+    }
+}
@@ -0,0 +1,386 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    path::{Component, Path, PathBuf},
+};
+
+use cargo_toml::{Dependency, DepsSet, Manifest, Value, Workspace};
+
+use crate::{json::Json, source::Source};
+
+/// Find the manifest with a `[workspace]` table the package at `manifest_path` belongs to.
+///
+/// Follows `package.workspace` when set, otherwise searches the parent directories for a
+/// workspace that lists the package as a member.
+pub fn find_root(
+    source: &Source,
+    manifest_path: &Path,
+    manifest: &Manifest,
+) -> Result<Option<PathBuf>, Box<dyn Error>> {
+    if manifest.workspace.is_some() {
+        return Ok(Some(manifest_path.to_owned()));
+    }
+
+    let package_dir = manifest_path.parent().ok_or("Invalid manifest path")?;
+
+    if let Some(hint) = manifest.package.as_ref().and_then(|p| p.workspace.as_ref()) {
+        let root = normalize(&package_dir.join(hint).join("Cargo.toml"));
+        if !source.is_file(&root) {
+            return Err(format!("No workspace manifest at {}", root.display()).into());
+        }
+        return match source.raw_manifest(&root)?.workspace {
+            Some(_) => Ok(Some(root)),
+            None => Err(format!("{} has no [workspace] table", root.display()).into()),
+        };
+    }
+
+    for dir in package_dir.ancestors().skip(1) {
+        let candidate = dir.join("Cargo.toml");
+        if !source.is_file(&candidate) {
+            continue;
+        }
+
+        if let Some(workspace) = source.raw_manifest(&candidate)?.workspace {
+            if is_member(dir, package_dir, &workspace) {
+                return Ok(Some(candidate));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Resolve `.` and `..` without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Whether `package_dir` is matched by the `members` globs and not by `exclude`
+pub fn is_member(root_dir: &Path, package_dir: &Path, workspace: &Workspace) -> bool {
+    let Ok(relative) = package_dir.strip_prefix(root_dir) else {
+        return false;
+    };
+    let relative = segments(relative);
+
+    !is_excluded(&relative, workspace)
+        && workspace
+            .members
+            .iter()
+            .any(|member| glob_match(&segments(Path::new(member)), &relative))
+}
+
+fn is_excluded(relative: &[String], workspace: &Workspace) -> bool {
+    workspace
+        .exclude
+        .iter()
+        .any(|exclude| relative.starts_with(&segments(Path::new(exclude))))
+}
+
+/// Directories of all workspace members, including the root if it is a package itself
+pub fn members(source: &Source, root_manifest: &Path, root: &Manifest) -> Vec<PathBuf> {
+    let Some(workspace) = &root.workspace else {
+        return Vec::new();
+    };
+    let root_dir = root_manifest.parent().unwrap_or(Path::new(""));
+
+    let mut members = Vec::new();
+    if root.package.is_some() {
+        members.push(root_dir.to_owned());
+    }
+
+    for pattern in &workspace.members {
+        for dir in expand(source, root_dir, &segments(Path::new(pattern))) {
+            let dir = normalize(&dir);
+            let relative = dir.strip_prefix(root_dir).map(segments).unwrap_or_default();
+            if source.is_file(&dir.join("Cargo.toml"))
+                && !is_excluded(&relative, workspace)
+                && !members.contains(&dir)
+            {
+                members.push(dir);
+            }
+        }
+    }
+
+    members
+}
+
+/// Expand a member glob against the existing directories
+fn expand(source: &Source, dir: &Path, pattern: &[String]) -> Vec<PathBuf> {
+    let Some((first, rest)) = pattern.split_first() else {
+        return vec![dir.to_owned()];
+    };
+
+    if !first.contains(['*', '?']) {
+        let next = dir.join(first);
+        return match first == ".." || source.is_dir(&next) {
+            true => expand(source, &next, rest),
+            false => Vec::new(),
+        };
+    }
+
+    let children = source.subdirs(dir);
+
+    if first == "**" {
+        let mut found = expand(source, dir, rest);
+        for child in children {
+            found.extend(expand(source, &child, pattern));
+        }
+        return found;
+    }
+
+    children
+        .iter()
+        .filter(|child| {
+            let name = child.file_name().unwrap_or_default().to_string_lossy();
+            segment_match(first.as_bytes(), name.as_bytes())
+        })
+        .flat_map(|child| expand(source, child, rest))
+        .collect()
+}
+
+fn segments(path: &Path) -> Vec<String> {
+    path.components()
+        .filter_map(|c| match c {
+            Component::Normal(s) => Some(s.to_string_lossy().to_string()),
+            Component::ParentDir => Some("..".to_owned()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Package names of the members that inherit each workspace dependency with `workspace = true`
+pub fn dependency_usage(
+    source: &Source,
+    root_manifest: &Path,
+    root: &Manifest,
+) -> Result<BTreeMap<String, Vec<String>>, Box<dyn Error>> {
+    let mut usage: BTreeMap<String, Vec<String>> = root
+        .workspace
+        .iter()
+        .flat_map(|ws| ws.dependencies.keys())
+        .map(|name| (name.clone(), Vec::new()))
+        .collect();
+
+    for dir in members(source, root_manifest, root) {
+        let member = source.raw_manifest(&dir.join("Cargo.toml"))?;
+        let Some(package) = &member.package else {
+            continue;
+        };
+
+        for (name, dep) in dependency_sets(&member).into_iter().flatten() {
+            if let (Dependency::Inherited(_), Some(users)) = (dep, usage.get_mut(name)) {
+                if !users.iter().any(|user| user == package.name()) {
+                    users.push(package.name().to_owned());
+                }
+            }
+        }
+    }
+
+    Ok(usage)
+}
+
+/// Normal, dev and build dependencies, including the target specific ones
+pub fn dependency_sets(manifest: &Manifest) -> Vec<&DepsSet> {
+    let mut sets = vec![
+        &manifest.dependencies,
+        &manifest.dev_dependencies,
+        &manifest.build_dependencies,
+    ];
+    for target in manifest.target.values() {
+        sets.extend([
+            &target.dependencies,
+            &target.dev_dependencies,
+            &target.build_dependencies,
+        ]);
+    }
+    sets
+}
+
+/// `name version features=a,b path=..` with only the parts that are set
+pub fn describe_dependency(name: &str, dep: &Dependency) -> String {
+    let mut parts = vec![name.to_owned()];
+
+    match dep.detail() {
+        None => parts.push(dep.req().to_owned()),
+        Some(detail) => {
+            parts.extend(detail.version.clone());
+            if !detail.features.is_empty() {
+                parts.push(format!("features={}", detail.features.join(",")));
+            }
+            let source = [
+                ("package", &detail.package),
+                ("path", &detail.path),
+                ("git", &detail.git),
+                ("branch", &detail.branch),
+                ("tag", &detail.tag),
+                ("rev", &detail.rev),
+                ("registry", &detail.registry),
+            ];
+            for (key, value) in source {
+                if let Some(value) = value {
+                    parts.push(format!("{key}={value}"));
+                }
+            }
+        }
+    }
+
+    parts.join(" ")
+}
+
+pub fn dependency_json(name: &str, dep: &Dependency) -> Json {
+    let detail = dep.detail().cloned().unwrap_or_default();
+    let version = match dep.detail() {
+        None => Some(dep.req().to_owned()),
+        Some(_) => detail.version,
+    };
+
+    Json::object(vec![
+        ("name", Json::from(name)),
+        ("version", version.into()),
+        ("features", detail.features.into_iter().collect()),
+        ("package", detail.package.into()),
+        ("path", detail.path.into()),
+        ("git", detail.git.into()),
+        ("branch", detail.branch.into()),
+        ("tag", detail.tag.into()),
+        ("rev", detail.rev.into()),
+        ("registry", detail.registry.into()),
+    ])
+}
+
+/// Look up a dotted key in a metadata table
+pub fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.')
+        .try_fold(value, |value, part| value.as_table()?.get(part))
+}
+
+/// Strings are printed raw, arrays one item per entry and tables as `key = value` pairs
+pub fn render_value(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(items) => items.iter().flat_map(render_value).collect(),
+        Value::Table(table) => table
+            .iter()
+            .flat_map(|(key, value)| match value {
+                Value::Table(_) => render_value(value)
+                    .into_iter()
+                    .map(|line| format!("{key}.{line}"))
+                    .collect(),
+                value => vec![format!("{key} = {value}")],
+            })
+            .collect(),
+        value => vec![value.to_string()],
+    }
+}
+
+/// Match path segments against a glob supporting `*`, `?` and `**`
+fn glob_match(pattern: &[String], path: &[String]) -> bool {
+    match (pattern.first(), path.first()) {
+        (None, None) => true,
+        (Some(p), _) if p == "**" => {
+            glob_match(&pattern[1..], path) || (!path.is_empty() && glob_match(pattern, &path[1..]))
+        }
+        (Some(p), Some(s)) => {
+            segment_match(p.as_bytes(), s.as_bytes()) && glob_match(&pattern[1..], &path[1..])
+        }
+        _ => false,
+    }
+}
+
+fn segment_match(pattern: &[u8], s: &[u8]) -> bool {
+    match (pattern.first(), s.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            segment_match(&pattern[1..], s) || (!s.is_empty() && segment_match(pattern, &s[1..]))
+        }
+        (Some(b'?'), Some(_)) => segment_match(&pattern[1..], &s[1..]),
+        (Some(p), Some(c)) if p == c => segment_match(&pattern[1..], &s[1..]),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn is_member_ok() {
+        let root = Path::new("/ws");
+        let ws = Manifest::from_str(
+            r#"
+            [workspace]
+            members = ["pkg1", "crates/*", "tools/**/cli"]
+            exclude = ["crates/old"]
+            "#,
+        )
+        .unwrap()
+        .workspace
+        .unwrap();
+
+        assert!(is_member(root, Path::new("/ws/pkg1"), &ws));
+        assert!(is_member(root, Path::new("/ws/crates/foo"), &ws));
+        assert!(is_member(root, Path::new("/ws/tools/cli"), &ws));
+        assert!(is_member(root, Path::new("/ws/tools/a/b/cli"), &ws));
+
+        assert!(!is_member(root, Path::new("/ws/pkg2"), &ws));
+        assert!(!is_member(root, Path::new("/ws/crates/old"), &ws));
+        assert!(!is_member(root, Path::new("/ws/crates/foo/bar"), &ws));
+        assert!(!is_member(root, Path::new("/other/pkg1"), &ws));
+    }
+
+    #[test]
+    fn describe_dependency_ok() {
+        let ws = Manifest::from_str(
+            r#"
+            [workspace.dependencies]
+            log = "0.4"
+            serde = { version = "1", features = ["derive"] }
+            pkg1 = { path = "pkg1" }
+            "#,
+        )
+        .unwrap()
+        .workspace
+        .unwrap();
+        let describe = |name: &str| describe_dependency(name, &ws.dependencies[name]);
+
+        assert_eq!(describe("log"), "log 0.4");
+        assert_eq!(describe("serde"), "serde 1 features=derive");
+        assert_eq!(describe("pkg1"), "pkg1 path=pkg1");
+    }
+
+    #[test]
+    fn render_value_ok() {
+        let value: Value = toml::from_str(
+            r#"
+            name = "release"
+            targets = ["x86_64", "aarch64"]
+            ci.cache = true
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            render_value(&value),
+            vec![
+                "ci.cache = true",
+                "name = \"release\"",
+                "targets = [\"x86_64\", \"aarch64\"]"
+            ]
+        );
+        assert_eq!(
+            render_value(lookup(&value, "targets").unwrap()),
+            vec!["x86_64", "aarch64"]
+        );
+        assert!(lookup(&value, "ci.missing").is_none());
+    }
+}
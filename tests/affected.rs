@@ -0,0 +1,51 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn affected_dependents() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/graph").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("workspace.affected")
+        .arg("--delimiter=LF")
+        .write_stdin("tests/data/graph/crates/core/src/lib.rs\nREADME.md\n")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"graph-app\ngraph-core\ngraph-testkit\n" as &[u8],
+        ));
+}
+
+#[test]
+fn affected_paths_from_file() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/graph").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("workspace.affected")
+        .arg("--file=changed.txt")
+        .arg("--base-dir=.")
+        .arg("--format=paths")
+        .arg("--delimiter=LF")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"crates/app\ncrates/macros\n" as &[u8]));
+}
+
+#[test]
+fn affected_matrix_root_lockfile() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/workspace/pkg1").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("workspace.affected")
+        .arg("--format=matrix")
+        .write_stdin("tests/data/workspace/Cargo.lock\n")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"{\"include\":[{\"name\":\"pkg1\",\"path\":\"pkg1\"},{\"name\":\"pkg2\",\"path\":\"pkg2\"}]}\n"
+                as &[u8],
+        ));
+}
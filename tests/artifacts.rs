@@ -0,0 +1,119 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn artifacts_linux() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/artifacts").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("artifacts")
+        .arg("--target=x86_64-unknown-linux-gnu")
+        .arg("--delimiter=LF")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"libfoo_bar.rlib\nlibfoo_bar.so\nlibfoo_bar.a\nfoo-cli\n" as &[u8],
+        ));
+}
+
+#[test]
+fn artifacts_windows() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/artifacts").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("artifacts")
+        .arg("--target=x86_64-pc-windows-msvc")
+        .arg("--delimiter=LF")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"libfoo_bar.rlib\nfoo_bar.dll\nfoo_bar.dll.lib\nfoo_bar.lib\nfoo-cli.exe\n" as &[u8],
+        ));
+}
+
+fn paths_cmd(dir: &str) -> Command {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize(dir).unwrap();
+    let home = std::fs::canonicalize("tests/data/config/cargo_home").unwrap();
+    cmd.current_dir(p)
+        .env("CARGO_HOME", home)
+        .env_remove("CARGO_TARGET_DIR")
+        .env_remove("CARGO_BUILD_TARGET_DIR");
+    cmd
+}
+
+#[test]
+fn artifacts_paths() {
+    let root = std::fs::canonicalize("tests/data/workspace").unwrap();
+    let expected = root.join("target/aarch64-apple-darwin/release/pkg2");
+
+    paths_cmd("tests/data/workspace/pkg2")
+        .arg("artifacts")
+        .arg("--target=aarch64-apple-darwin")
+        .arg("--paths")
+        .arg("--profile=release")
+        .assert()
+        .success()
+        .stdout(predicate::eq(format!("{}\n", expected.display())));
+}
+
+#[test]
+fn artifacts_paths_config_target_dir() {
+    let root = std::fs::canonicalize("tests/data/artifacts").unwrap();
+    let expected = root.join("build/x86_64-unknown-linux-gnu/debug/foo-cli");
+
+    paths_cmd("tests/data/artifacts/src")
+        .arg("artifacts")
+        .arg("--target=x86_64-unknown-linux-gnu")
+        .arg("--paths")
+        .arg("--delimiter=LF")
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with(format!(
+            "{}\n",
+            expected.display()
+        )));
+}
+
+#[test]
+fn artifacts_paths_env_target_dir() {
+    paths_cmd("tests/data/workspace/pkg2")
+        .env("CARGO_TARGET_DIR", "/tmp/cargo-target")
+        .arg("artifacts")
+        .arg("--target=aarch64-apple-darwin")
+        .arg("--paths")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"/tmp/cargo-target/aarch64-apple-darwin/debug/pkg2\n" as &[u8],
+        ));
+}
+
+#[test]
+fn artifacts_paths_explicit_target_dir() {
+    paths_cmd("tests/data/workspace/pkg2")
+        .env("CARGO_TARGET_DIR", "/tmp/cargo-target")
+        .arg("artifacts")
+        .arg("--target=aarch64-apple-darwin")
+        .arg("--paths")
+        .arg("--target-dir=out")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"out/aarch64-apple-darwin/debug/pkg2\n" as &[u8],
+        ));
+}
+
+#[test]
+fn artifacts_unknown_target() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/artifacts").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("artifacts")
+        .arg("--target=x86_64-unknown-nowhere")
+        .assert()
+        .failure();
+}
@@ -0,0 +1,63 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn author_names() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_02").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("package.authors")
+        .arg("--names")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"John Doe\r\nJane Doe\n" as &[u8]));
+}
+
+#[test]
+fn author_emails() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_02").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("package.authors")
+        .arg("--emails")
+        .arg("--delimiter=,")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"john-doe@abc.com,jane-doe@def.com\n" as &[u8],
+        ));
+}
+
+#[test]
+fn author_json() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_02").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("package.authors")
+        .arg("--json")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            br#"[{"name":"John Doe","email":"john-doe@abc.com"},{"name":"Jane Doe","email":"jane-doe@def.com"}]
+"# as &[u8],
+        ));
+}
+
+#[test]
+fn workspace_author_without_email() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_05").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("workspace.package.authors")
+        .arg("--json")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            br#"[{"name":"Nice Folks","email":null}]
+"# as &[u8],
+        ));
+}
@@ -0,0 +1,86 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn changelog_current_version() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/changelog").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("changelog")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"### Added
+
+- Output as JSON
+
+  ```bash
+  # print the report as JSON
+  changelog --json
+  ```
+
+### Fixed
+
+- Crash on empty input
+" as &[u8],
+        ));
+}
+
+#[test]
+fn changelog_version() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/changelog").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("changelog")
+        .arg("--version=v1.0.0")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"- Initial release\n" as &[u8]));
+}
+
+#[test]
+fn changelog_check_unreleased() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/changelog").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("changelog")
+        .arg("--check")
+        .assert()
+        .failure()
+        .stderr(predicate::eq(
+            b"Error: The `[Unreleased]` section of CHANGELOG.md is not empty\n" as &[u8],
+        ));
+}
+
+#[test]
+fn changelog_file() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/changelog").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("changelog")
+        .arg("--file=HISTORY.md")
+        .arg("--check")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"* Output as JSON\n" as &[u8]));
+}
+
+#[test]
+fn changelog_missing_version() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/changelog").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("changelog")
+        .arg("--file=HISTORY.md")
+        .arg("--version=2.0.0")
+        .assert()
+        .failure()
+        .stderr(predicate::eq(
+            b"Error: No section for version 2.0.0 in HISTORY.md\n" as &[u8],
+        ));
+}
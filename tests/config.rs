@@ -0,0 +1,98 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn config_cmd() -> Command {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/config/nested").unwrap();
+    let home = std::fs::canonicalize("tests/data/config/cargo_home").unwrap();
+    cmd.current_dir(p)
+        .env("CARGO_HOME", home)
+        .env_remove("CARGO_BUILD_TARGET");
+    cmd
+}
+
+#[test]
+fn config_nearest_file_wins() {
+    config_cmd()
+        .arg("config")
+        .arg("build.target")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"wasm32-unknown-unknown\n" as &[u8]));
+}
+
+#[test]
+fn config_arrays_are_joined() {
+    config_cmd()
+        .arg("config")
+        .arg("build.rustflags")
+        .arg("--delimiter=LF")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"-Dwarnings\n-Cdebuginfo=0\n" as &[u8]));
+}
+
+#[test]
+fn config_table_merged_with_cargo_home() {
+    config_cmd()
+        .arg("config")
+        .arg("alias")
+        .arg("--delimiter=LF")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"b = \"build\"\nc = \"check\"\nt = \"test\"\n" as &[u8],
+        ));
+}
+
+#[test]
+fn config_registry_index() {
+    config_cmd()
+        .arg("config")
+        .arg("registries.my-registry.index")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"sparse+https://example.com/index/\n" as &[u8],
+        ));
+}
+
+#[test]
+fn config_env_override() {
+    config_cmd()
+        .env("CARGO_BUILD_TARGET", "aarch64-apple-darwin")
+        .arg("config")
+        .arg("build.target")
+        .arg("--show-origin")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"env:CARGO_BUILD_TARGET\taarch64-apple-darwin\n" as &[u8],
+        ));
+}
+
+#[test]
+fn config_show_origin() {
+    let file = std::fs::canonicalize("tests/data/config/.cargo/config.toml").unwrap();
+    let expected = format!("file:{}\tcustom-target\n", file.display());
+
+    config_cmd()
+        .arg("config")
+        .arg("build.target-dir")
+        .arg("--show-origin")
+        .assert()
+        .success()
+        .stdout(predicate::eq(expected.as_bytes()));
+}
+
+#[test]
+fn config_missing_key() {
+    config_cmd()
+        .arg("config")
+        .arg("build.jobs")
+        .assert()
+        .failure()
+        .stderr(predicate::eq(
+            b"Error: `build.jobs` not specified in cargo config\n" as &[u8],
+        ));
+}
@@ -0,0 +1,128 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn debian_control() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/debian").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("debian")
+        .arg("control")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"Package: log-shipper
+Version: 0.4.0~beta.2-2
+Architecture: amd64
+Section: admin
+Priority: optional
+Maintainer: Packaging Team <packaging@example.com>
+Depends: libc6 (>= 2.31), libssl3
+Homepage: https://github.com/example/log-shipper
+Description: Ship log files to a remote collector
+ # log_shipper
+ .
+ Tails log files and ships new lines to a remote collector.
+ .
+ Supports rotation and compression.
+" as &[u8],
+        ));
+}
+
+#[test]
+fn debian_control_defaults() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_01").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("debian")
+        .arg("control")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"Package: test-name
+Version: 1.2.3-1
+Architecture: any
+Maintainer: John Doe <john-doe@abc.com>
+Homepage: https://github.com/nicolaiunrein/cargo-get
+Description: A very useful description
+" as &[u8],
+        ));
+}
+
+#[test]
+fn debian_copyright() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/debian").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("debian")
+        .arg("copyright")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"Format: https://www.debian.org/doc/packaging-manuals/copyright-format/1.0/
+Upstream-Name: log_shipper
+Source: https://github.com/example/log-shipper
+
+Files: *
+Copyright: 2024 ACME Corp.
+License: MIT or Apache-2.0
+
+License: MIT
+ Permission is hereby granted, free of charge, to any person obtaining a copy
+ of this software and associated documentation files (the \"Software\"), to deal
+ in the Software without restriction, including without limitation the rights
+ to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ copies of the Software, and to permit persons to whom the Software is
+ furnished to do so, subject to the following conditions:
+ .
+ The above copyright notice and this permission notice shall be included in all
+ copies or substantial portions of the Software.
+ .
+ THE SOFTWARE IS PROVIDED \"AS IS\", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ SOFTWARE.
+
+License: Apache-2.0
+ On Debian systems, the complete text of this license can be found in
+ \"/usr/share/common-licenses/Apache-2.0\".
+" as &[u8],
+        ));
+}
+
+#[test]
+fn debian_copyright_authors() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/lock/lib").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("debian")
+        .arg("copyright")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Copyright: Jane Doe <jane@example.com>\n ACME\nLicense: MIT or Apache-2.0\n",
+        ));
+}
+
+#[test]
+fn debian_copyright_missing_license_text() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_01").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("debian")
+        .arg("copyright")
+        .assert()
+        .failure()
+        .stderr(predicate::eq(
+            b"Error: No license text for MIT, add a LICENSE-MIT file next to Cargo.toml\n"
+                as &[u8],
+        ));
+}
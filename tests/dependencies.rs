@@ -0,0 +1,49 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn dependencies_for_linux() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/platform").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("dependencies")
+        .arg("--target=x86_64-unknown-linux-gnu")
+        .arg("--delimiter=LF")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"libc 0.2\nlinux-raw-sys 0.6\nlog 0.4\n" as &[u8],
+        ));
+}
+
+#[test]
+fn dependencies_for_wasm() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/platform").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("dependencies")
+        .arg("--target=wasm32-unknown-unknown")
+        .arg("--delimiter=LF")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"log 0.4\nwasm-bindgen 0.2\n" as &[u8]));
+}
+
+#[test]
+fn dependencies_with_extra_cfg() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/platform").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("dependencies")
+        .arg("--target=x86_64-pc-windows-msvc")
+        .arg("--cfg=tokio_unstable")
+        .arg("--delimiter=LF")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"log 0.4\ntokio-metrics 0.3\nwindows-sys 0.59\n" as &[u8],
+        ));
+}
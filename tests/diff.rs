@@ -0,0 +1,77 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn diff_directories() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/diff").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("diff")
+        .arg("old")
+        .arg("new/Cargo.toml")
+        .arg("--delimiter=LF")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"+ package.rust_version 1.70
+~ package.version 1.2.3 -> 1.3.0 (minor)
+~ package.description Before -> After
+- package.homepage https://example.com
+- package.keywords cli
++ package.keywords manifest
+" as &[u8],
+        ));
+}
+
+#[test]
+fn diff_unchanged() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/diff").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("diff")
+        .arg("old")
+        .arg("old/Cargo.toml")
+        .arg("--fail-on=any")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"\n" as &[u8]));
+}
+
+#[test]
+fn diff_fail_on() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/diff").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("diff")
+        .arg("old")
+        .arg("new")
+        .arg("--fail-on=major,package.description")
+        .assert()
+        .failure()
+        .stderr(predicate::eq(
+            b"Error: Unexpected manifest changes:\n  ~ package.description Before -> After\n"
+                as &[u8],
+        ));
+}
+
+#[test]
+fn diff_relative_to_entry() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("diff")
+        .arg("--entry=diff")
+        .arg("old")
+        .arg("new")
+        .arg("--fail-on=version")
+        .assert()
+        .failure()
+        .stderr(predicate::eq(
+            b"Error: Unexpected manifest changes:\n  ~ package.version 1.2.3 -> 1.3.0 (minor)\n"
+                as &[u8],
+        ));
+}
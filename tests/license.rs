@@ -0,0 +1,56 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn license_ids() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_01").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("package.license")
+        .arg("--ids")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"Apache-2.0\r\nMIT\n" as &[u8]));
+}
+
+#[test]
+fn license_normalize() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_01").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("package.license")
+        .arg("--normalize")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"MIT OR Apache-2.0\n" as &[u8]));
+}
+
+#[test]
+fn license_check_deprecated_syntax() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_01").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("package.license")
+        .arg("--check")
+        .assert()
+        .failure()
+        .stderr(predicate::eq(
+            b"Error: License check failed: deprecated syntax `/`, use `OR`\n" as &[u8],
+        ));
+}
+
+#[test]
+fn license_ids_normalize_conflict() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_01").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("package.license")
+        .arg("--ids")
+        .arg("--normalize")
+        .assert()
+        .failure();
+}
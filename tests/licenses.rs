@@ -0,0 +1,61 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn cmd() -> Command {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    cmd.current_dir(std::fs::canonicalize("tests/data/lock").unwrap());
+    cmd.env(
+        "CARGO_HOME",
+        std::fs::canonicalize("tests/data/licenses").unwrap(),
+    );
+    cmd
+}
+
+#[test]
+fn licenses() {
+    cmd()
+        .arg("licenses")
+        .arg("--delimiter=LF")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"MIT OR Apache-2.0: bitflags 2.6.0, proc-macro2 1.0.92, syn 2.0.90
+Unicode-DFS-2016 AND (MIT OR Apache-2.0): unicode-ident 1.0.14
+license-file: internal 0.3.1
+missing: bitflags 1.3.2, patched 0.1.0
+" as &[u8],
+        ));
+}
+
+#[test]
+fn licenses_allow() {
+    cmd()
+        .arg("licenses")
+        .arg("--allow=MIT,Apache-2.0")
+        .assert()
+        .failure()
+        .stderr(predicate::eq(
+            b"Error: Disallowed licenses:
+  unicode-ident 1.0.14: Unicode-DFS-2016 AND (MIT OR Apache-2.0)
+  internal 0.3.1: license-file
+  bitflags 1.3.2: not in the registry cache
+  patched 0.1.0: git dependency
+" as &[u8],
+        ));
+}
+
+#[test]
+fn licenses_deny() {
+    cmd()
+        .arg("licenses")
+        .arg("--deny=Apache-2.0")
+        .assert()
+        .failure()
+        .stderr(predicate::eq(
+            b"Error: Disallowed licenses:
+  internal 0.3.1: license-file
+  bitflags 1.3.2: not in the registry cache
+  patched 0.1.0: git dependency
+" as &[u8],
+        ));
+}
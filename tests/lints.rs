@@ -0,0 +1,42 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn lints_inherited_from_workspace() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/workspace/pkg1").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("lints")
+        .arg("--tool=rust")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"rust.unsafe_code = \"forbid\"\n" as &[u8]));
+}
+
+#[test]
+fn lints_as_flags() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/workspace/pkg1").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("lints")
+        .arg("--as-flags")
+        .arg("--delimiter= ")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"-D clippy::all -W clippy::unwrap_used -F unsafe_code\n" as &[u8],
+        ));
+}
+
+#[test]
+fn lints_not_specified() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/workspace/pkg2").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("lints").assert().failure().stderr(predicate::eq(
+        b"Error: `lints` not specified in manifest\n" as &[u8],
+    ));
+}
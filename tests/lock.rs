@@ -0,0 +1,98 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn lock_summary() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/lock/app").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("lock.summary")
+        .arg("--delimiter=LF")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"version: 4
+packages: 9
+sources: crates.io 5, registry 1, git 1, path 2
+duplicate: bitflags 1.3.2 (app), 2.6.0 (app, lib)
+" as &[u8],
+        ));
+}
+
+#[test]
+fn lock_summary_json() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/lock").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("lock.summary")
+        .arg("--json")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            br#"{"version":4,"packages":9,"sources":{"crates.io":5,"registry":1,"git":1,"path":2},"duplicates":[{"name":"bitflags","versions":[{"version":"1.3.2","members":["app"]},{"version":"2.6.0","members":["app","lib"]}]}]}
+"# as &[u8],
+        ));
+}
+
+#[test]
+fn lock_summary_missing() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_02").unwrap();
+    cmd.current_dir(&p);
+
+    let expected = format!(
+        "Error: No Cargo.lock found at {}\n",
+        p.join("Cargo.lock").display()
+    );
+    cmd.arg("lock.summary")
+        .assert()
+        .failure()
+        .stderr(predicate::eq(expected.as_bytes()));
+}
+
+#[test]
+fn check_lock_ok() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/lock").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("check")
+        .arg("lock")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"" as &[u8]));
+}
+
+#[test]
+fn check_lock_drift() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/lock_drift").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("check")
+        .arg("lock")
+        .assert()
+        .failure()
+        .stderr(predicate::eq(
+            b"Error: Cargo.lock is out of sync with the manifests:
+  drift: locked at 0.1.0 but the manifest has version 0.2.0
+  drift: semver = \"1.0.26\" is not satisfied by locked 1.0.20
+  orphaned: unused 0.1.0
+" as &[u8],
+        ));
+}
+
+#[test]
+fn check_lock_inherited() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/lock_inherited/app").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("check")
+        .arg("lock")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"" as &[u8]));
+}
@@ -0,0 +1,65 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn oci_labels_args() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/lock/lib").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("oci-labels")
+        .arg("--revision=4f1c2d0")
+        .arg("--delimiter=LF")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"--label 'org.opencontainers.image.title=lib'
+--label 'org.opencontainers.image.description=Shared <internals> & helpers'
+--label 'org.opencontainers.image.version=0.2.0'
+--label 'org.opencontainers.image.licenses=MIT OR Apache-2.0'
+--label 'org.opencontainers.image.source=https://github.com/example/lib'
+--label 'org.opencontainers.image.url=https://github.com/example/lib'
+--label 'org.opencontainers.image.documentation=https://docs.rs/lib/0.2.0'
+--label 'org.opencontainers.image.authors=Jane Doe <jane@example.com>, ACME'
+--label 'org.opencontainers.image.revision=4f1c2d0'
+" as &[u8],
+        ));
+}
+
+#[test]
+fn oci_labels_dockerfile() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_01").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("oci-labels")
+        .arg("--format=dockerfile")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            br#"LABEL org.opencontainers.image.title="test-name" \
+      org.opencontainers.image.description="A very useful description" \
+      org.opencontainers.image.version="1.2.3" \
+      org.opencontainers.image.licenses="MIT OR Apache-2.0" \
+      org.opencontainers.image.source="https://github.com/nicolaiunrein/cargo-get" \
+      org.opencontainers.image.url="https://github.com/nicolaiunrein/cargo-get" \
+      org.opencontainers.image.documentation="https://docs.rs/test-name/1.2.3" \
+      org.opencontainers.image.authors="John Doe<john-doe@abc.com>"
+"# as &[u8],
+        ));
+}
+
+#[test]
+fn oci_labels_json() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_01").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("oci-labels")
+        .arg("--format=json")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            r#"{"org.opencontainers.image.title":"test-name","org.opencontainers.image.description":"A very useful description","#,
+        ));
+}
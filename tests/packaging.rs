@@ -0,0 +1,178 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn packaging_rpm_spec() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/packaging").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("packaging")
+        .arg("--format=rpm-spec")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"Name:           log_shipper
+Version:        0.4.0~beta.2
+Release:        3%{?dist}
+Summary:        Ship log files to a collector, it's fast
+License:        MIT OR Apache-2.0
+URL:            https://github.com/example/log-shipper
+
+BuildRequires:  cargo
+BuildRequires:  rust
+Requires:       openssl-libs >= 3
+Requires:       systemd
+
+%description
+Ship log files to a collector, it's fast
+
+%build
+cargo build --release --locked
+
+%install
+install -Dm755 target/release/log_shipper %{buildroot}/usr/bin/log_shipper
+install -Dm755 target/release/log-tail %{buildroot}/usr/bin/log-tail
+install -Dm644 dist/log_shipper.service %{buildroot}/usr/lib/systemd/system/log_shipper.service
+
+%files
+/usr/bin/log_shipper
+/usr/bin/log-tail
+/usr/lib/systemd/system/log_shipper.service
+" as &[u8],
+        ));
+}
+
+#[test]
+fn packaging_rpm_spec_bins() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_01").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("packaging")
+        .arg("--format=rpm-spec")
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with(
+            "%install
+install -Dm755 target/release/test-name %{buildroot}%{_bindir}/test-name
+
+%files
+%{_bindir}/test-name
+",
+        ));
+}
+
+#[test]
+fn packaging_pkgbuild() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/packaging").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("packaging")
+        .arg("--format=pkgbuild")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            br#"pkgname=log_shipper
+pkgver=0.4.0beta.2
+pkgrel=1
+pkgdesc='Ship log files to a collector, it'\''s fast'
+arch=('x86_64')
+url='https://github.com/example/log-shipper'
+license=('MIT OR Apache-2.0')
+depends=()
+makedepends=('cargo')
+source=()
+sha256sums=()
+
+build() {
+  cargo build --release --locked
+}
+
+package() {
+  install -Dm755 target/release/log-tail "$pkgdir/usr/bin/log-tail"
+  install -Dm755 target/release/log_shipper "$pkgdir/usr/bin/log_shipper"
+}
+"# as &[u8],
+        ));
+}
+
+#[test]
+fn packaging_no_bin() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/debian").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("packaging")
+        .arg("--format=pkgbuild")
+        .assert()
+        .failure()
+        .stderr(predicate::eq(
+            b"Error: No [[bin]] targets to package\n" as &[u8],
+        ));
+}
+
+#[test]
+fn packaging_homebrew() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/packaging").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("packaging")
+        .arg("--format=homebrew")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            br#"class LogShipper < Formula
+  desc "Ship log files to a collector, it's fast"
+  homepage "https://github.com/example/log-shipper"
+  url "https://github.com/example/log-shipper/archive/refs/tags/v0.4.0-beta.2.tar.gz"
+  sha256 "0000000000000000000000000000000000000000000000000000000000000000"
+  license any_of: ["MIT", "Apache-2.0"]
+
+  depends_on "rust" => :build
+
+  def install
+    system "cargo", "install", *std_cargo_args
+  end
+end
+"# as &[u8],
+        ));
+}
+
+#[test]
+fn packaging_nix() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_01").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("packaging")
+        .arg("--format=nix")
+        .arg("--tag-pattern={name}-{version}")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            br#"{ lib, rustPlatform, fetchzip }:
+
+rustPlatform.buildRustPackage {
+  pname = "test-name";
+  version = "1.2.3";
+
+  src = fetchzip {
+    url = "https://github.com/nicolaiunrein/cargo-get/archive/refs/tags/test-name-1.2.3.tar.gz";
+    hash = lib.fakeHash;
+  };
+
+  cargoHash = lib.fakeHash;
+
+  meta = {
+    description = "A very useful description";
+    homepage = "https://github.com/nicolaiunrein/cargo-get";
+    license = with lib.licenses; [ mit asl20 ];
+    mainProgram = "test-name";
+  };
+}
+"# as &[u8],
+        ));
+}
@@ -0,0 +1,127 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::path::{Path, PathBuf};
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+/// A workspace committed at tag `v1.0.0`, with a newer version committed on top and an
+/// uncommitted version in the working tree
+fn repo(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("cargo-get-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("pkg/src")).unwrap();
+
+    let write_version = |version: &str| {
+        let manifest =
+            format!("[workspace]\nmembers = [\"pkg\"]\npackage.version = \"{version}\"\n");
+        std::fs::write(dir.join("Cargo.toml"), manifest).unwrap();
+    };
+
+    git(&dir, &["init", "--quiet"]);
+    write_version("1.0.0");
+    std::fs::write(
+        dir.join("pkg/Cargo.toml"),
+        "[package]\nname = \"pkg\"\nversion.workspace = true\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("pkg/src/lib.rs"), "").unwrap();
+    git(&dir, &["add", "."]);
+    git(&dir, &["commit", "--quiet", "-m", "first"]);
+    git(&dir, &["tag", "v1.0.0"]);
+
+    write_version("1.1.0");
+    git(&dir, &["commit", "--quiet", "-am", "second"]);
+
+    write_version("2.0.0");
+    std::fs::create_dir_all(dir.join("new")).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+#[test]
+fn version_at_revisions() {
+    let dir = repo("revisions");
+
+    for (rev, expected) in [
+        (None, "2.0.0\n"),
+        (Some("HEAD"), "1.1.0\n"),
+        (Some("v1.0.0"), "1.0.0\n"),
+    ] {
+        let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+        cmd.current_dir(dir.join("pkg")).arg("package.version");
+        if let Some(rev) = rev {
+            cmd.arg(format!("--rev={rev}"));
+        }
+        cmd.assert()
+            .success()
+            .stdout(predicate::eq(expected.as_bytes()));
+    }
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn unknown_revision() {
+    let dir = repo("unknown");
+
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    cmd.current_dir(dir.join("pkg"));
+
+    cmd.arg("package.version")
+        .arg("--rev=v9.9.9")
+        .assert()
+        .failure()
+        .stderr(predicate::eq(
+            b"Error: Unknown git revision `v9.9.9`\n" as &[u8],
+        ));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn path_missing_at_revision() {
+    let dir = repo("missing");
+
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    cmd.current_dir(dir.join("new"));
+
+    cmd.arg("package.version")
+        .arg("--rev=HEAD")
+        .assert()
+        .failure()
+        .stderr(predicate::eq(
+            b"Error: `new` does not exist at revision `HEAD`\n" as &[u8],
+        ));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn diff_revisions() {
+    let dir = repo("diff");
+
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    cmd.current_dir(dir.join("pkg"));
+
+    cmd.arg("diff")
+        .arg("v1.0.0")
+        .arg(".")
+        .arg("--delimiter=LF")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"~ package.version 1.0.0 -> 2.0.0 (major)
+~ workspace.package.version 1.0.0 -> 2.0.0 (major)
+" as &[u8],
+        ));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
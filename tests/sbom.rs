@@ -0,0 +1,141 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn cyclonedx_json() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/lock/lib").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("sbom")
+        .arg("--format=cyclonedx-json")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            r#"{"bomFormat":"CycloneDX","specVersion":"1.6","version":1,"metadata":{"component":{"type":"library","bom-ref":"pkg:cargo/lib@0.2.0","authors":[{"name":"Jane Doe","email":"jane@example.com"},{"name":"ACME"}],"name":"lib","version":"0.2.0","purl":"pkg:cargo/lib@0.2.0","description":"Shared <internals> & helpers","licenses":[{"expression":"MIT OR Apache-2.0"}],"externalReferences":[{"type":"vcs","url":"https://github.com/example/lib"}]}},"components":[{"type":"library","bom-ref":"pkg:cargo/bitflags@2.6.0","name":"bitflags","version":"2.6.0","hashes":[{"alg":"SHA-256","content":"b048fb63fd8b5923fc5aa7b340d8e156aec7ec02f0c78fa8a6ddc2613f6f71de"}],"purl":"pkg:cargo/bitflags@2.6.0"},"#,
+        ))
+        .stdout(predicate::str::contains(
+            r#"{"ref":"pkg:cargo/lib@0.2.0","dependsOn":["pkg:cargo/bitflags@2.6.0","pkg:cargo/syn@2.0.90"]}"#,
+        ))
+        // Only the dependencies of `lib`, not the rest of the workspace
+        .stdout(predicate::str::contains("bitflags@1.3.2").not());
+}
+
+#[test]
+fn cyclonedx_xml() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/lock/lib").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("sbom")
+        .arg("--format=cyclonedx-xml")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<bom xmlns="http://cyclonedx.org/schema/bom/1.6" version="1">
+  <metadata>
+    <component type="library" bom-ref="pkg:cargo/lib@0.2.0">
+      <authors>
+        <author><name>Jane Doe</name><email>jane@example.com</email></author>
+        <author><name>ACME</name></author>
+      </authors>
+      <name>lib</name>
+      <version>0.2.0</version>
+      <description>Shared &lt;internals&gt; &amp; helpers</description>
+      <licenses><expression>MIT OR Apache-2.0</expression></licenses>
+      <purl>pkg:cargo/lib@0.2.0</purl>
+      <externalReferences><reference type="vcs"><url>https://github.com/example/lib</url></reference></externalReferences>
+    </component>
+  </metadata>
+"#,
+        ))
+        .stdout(predicate::str::contains(
+            r#"    <dependency ref="pkg:cargo/syn@2.0.90">
+      <dependency ref="pkg:cargo/proc-macro2@1.0.92"/>
+      <dependency ref="pkg:cargo/unicode-ident@1.0.14"/>
+    </dependency>
+"#,
+        ))
+        .stdout(predicate::str::ends_with("</bom>\n"));
+}
+
+#[test]
+fn spdx_tag_reproducible() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/lock/lib").unwrap();
+    cmd.current_dir(p).env("SOURCE_DATE_EPOCH", "1706702400");
+
+    cmd.arg("sbom")
+        .arg("--format=spdx-tag")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "SPDXVersion: SPDX-2.3
+DataLicense: CC0-1.0
+SPDXID: SPDXRef-DOCUMENT
+DocumentName: lib-0.2.0
+DocumentNamespace: https://spdx.org/spdxdocs/lib-0.2.0-1706702400
+Creator: Tool: cargo-get-1.4.0
+Created: 2024-01-31T12:00:00Z
+
+PackageName: lib
+SPDXID: SPDXRef-Package-lib-0.2.0
+PackageVersion: 0.2.0
+PackageDownloadLocation: git+https://github.com/example/lib
+FilesAnalyzed: false
+PackageLicenseConcluded: NOASSERTION
+PackageLicenseDeclared: MIT OR Apache-2.0
+PackageCopyrightText: NOASSERTION
+PackageDescription: <text>Shared <internals> & helpers</text>
+ExternalRef: PACKAGE-MANAGER purl pkg:cargo/lib@0.2.0
+
+PackageName: bitflags
+SPDXID: SPDXRef-Package-bitflags-2.6.0
+PackageVersion: 2.6.0
+PackageDownloadLocation: https://crates.io/api/v1/crates/bitflags/2.6.0/download
+FilesAnalyzed: false
+PackageChecksum: SHA256: b048fb63fd8b5923fc5aa7b340d8e156aec7ec02f0c78fa8a6ddc2613f6f71de
+PackageLicenseConcluded: NOASSERTION
+PackageLicenseDeclared: NOASSERTION
+PackageCopyrightText: NOASSERTION
+ExternalRef: PACKAGE-MANAGER purl pkg:cargo/bitflags@2.6.0
+",
+        ))
+        .stdout(predicate::str::ends_with(
+            "Relationship: SPDXRef-DOCUMENT DESCRIBES SPDXRef-Package-lib-0.2.0
+Relationship: SPDXRef-Package-lib-0.2.0 DEPENDS_ON SPDXRef-Package-bitflags-2.6.0
+Relationship: SPDXRef-Package-lib-0.2.0 DEPENDS_ON SPDXRef-Package-syn-2.0.90
+Relationship: SPDXRef-Package-proc-macro2-1.0.92 DEPENDS_ON SPDXRef-Package-unicode-ident-1.0.14
+Relationship: SPDXRef-Package-syn-2.0.90 DEPENDS_ON SPDXRef-Package-proc-macro2-1.0.92
+Relationship: SPDXRef-Package-syn-2.0.90 DEPENDS_ON SPDXRef-Package-unicode-ident-1.0.14
+",
+        ));
+}
+
+#[test]
+fn spdx_json_git_source() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/lock/app").unwrap();
+    cmd.current_dir(p).env("SOURCE_DATE_EPOCH", "0");
+
+    cmd.arg("sbom")
+        .arg("--format=spdx-json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#""creationInfo":{"created":"1970-01-01T00:00:00Z","creators":["Tool: cargo-get-1.4.0"]}"#,
+        ))
+        .stdout(predicate::str::contains(
+            r#""downloadLocation":"git+https://github.com/example/patched@4f1c2d0e8a9b7c6d5e4f3a2b1c0d9e8f7a6b5c4d""#,
+        ))
+        .stdout(predicate::str::contains(
+            r#"{"spdxElementId":"SPDXRef-Package-app-0.1.0","relationshipType":"DEPENDS_ON","relatedSpdxElement":"SPDXRef-Package-lib-0.2.0"}"#,
+        ))
+        .stdout(predicate::str::contains(
+            r#""referenceLocator":"pkg:cargo/patched@0.1.0?vcs_url=git%2Bhttps://github.com/example/patched%404f1c2d0e8a9b7c6d5e4f3a2b1c0d9e8f7a6b5c4d""#,
+        ))
+        .stdout(predicate::str::contains(
+            r#"{"spdxElementId":"SPDXRef-Package-app-0.1.0","relationshipType":"DEPENDS_ON","relatedSpdxElement":"SPDXRef-Package-internal-0.3.1-registry.example.com-index"}"#,
+        ));
+}
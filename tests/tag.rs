@@ -0,0 +1,63 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn check_tag() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_01").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("check")
+        .arg("tag")
+        .arg("refs/tags/v1.2.3")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"test-name\n" as &[u8]));
+}
+
+#[test]
+fn check_tag_mismatch() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_01").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("check")
+        .arg("tag")
+        .arg("v1.3.0")
+        .assert()
+        .failure()
+        .stderr(predicate::eq(
+            b"Error: Tag `v1.3.0` is version 1.3.0 but test-name is at version 1.2.3\n" as &[u8],
+        ));
+}
+
+#[test]
+fn check_tag_workspace_member() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/lock").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("check")
+        .arg("tag")
+        .arg("--tag-pattern={name}-v{version}")
+        .env("GITHUB_REF", "refs/tags/lib-v0.2.0")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"lib\n" as &[u8]));
+}
+
+#[test]
+fn check_tag_not_a_tag() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_01").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("check")
+        .arg("tag")
+        .env("GITHUB_REF", "refs/heads/main")
+        .assert()
+        .failure()
+        .stderr(predicate::eq(
+            b"Error: GITHUB_REF `refs/heads/main` is not a tag\n" as &[u8],
+        ));
+}
@@ -0,0 +1,69 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn documentation_falls_back_to_docs_rs() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_01").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("package.documentation")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"https://docs.rs/test-name/1.2.3\n" as &[u8]));
+}
+
+#[test]
+fn urls_slug() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_01").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("urls.slug")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"nicolaiunrein/cargo-get\n" as &[u8]));
+}
+
+#[test]
+fn urls_tag_with_pattern() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_01").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("urls.tag")
+        .arg("--tag-pattern={name}-v{version}")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"https://github.com/nicolaiunrein/cargo-get/releases/tag/test-name-v1.2.3\n"
+                as &[u8],
+        ));
+}
+
+#[test]
+fn urls_raw_file() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/toml_01").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("urls.raw")
+        .arg("README.md")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"https://raw.githubusercontent.com/nicolaiunrein/cargo-get/v1.2.3/README.md\n"
+                as &[u8],
+        ));
+}
+
+#[test]
+fn urls_without_repository() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/workspace/pkg1").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("urls.web").assert().failure().stderr(predicate::eq(
+        b"Error: `package.repository` not specified in manifest\n" as &[u8],
+    ));
+}
@@ -0,0 +1,92 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn version_sync_ok() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/version_sync").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("check")
+        .arg("version-sync")
+        .arg("package.json")
+        .arg("python/pyproject.toml")
+        .arg("dynamic/pyproject.toml")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"" as &[u8]));
+}
+
+#[test]
+fn version_sync_out_of_sync() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/version_sync").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("check")
+        .arg("version-sync")
+        .arg("stale/package.json")
+        .arg("stale/pyproject.toml")
+        .assert()
+        .failure()
+        .stderr(predicate::eq(
+            b"Error: Versions out of sync with package.version:
+  stale/package.json: 0.9.0, expected 1.0.0-rc.1
+  stale/pyproject.toml: 1.0.0-rc.0, expected 1.0.0rc1
+" as &[u8],
+        ));
+}
+
+#[test]
+fn version_sync_fix() {
+    let dir = std::env::temp_dir().join(format!("cargo-get-version-sync-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for file in ["package.json", "pyproject.toml"] {
+        std::fs::copy(
+            format!("tests/data/version_sync/stale/{file}"),
+            dir.join(file),
+        )
+        .unwrap();
+    }
+
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/version_sync").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("check")
+        .arg("version-sync")
+        .arg(dir.join("package.json"))
+        .arg(dir.join("pyproject.toml"))
+        .arg("--fix")
+        .arg("--delimiter=LF")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "package.json: 0.9.0 -> 1.0.0-rc.1\n",
+        ))
+        .stdout(predicate::str::contains(
+            "pyproject.toml: 1.0.0-rc.0 -> 1.0.0rc1\n",
+        ));
+
+    assert_eq!(
+        std::fs::read_to_string(dir.join("package.json")).unwrap(),
+        r#"{
+  "name": "bindings",
+  "dependencies": { "version": "0.1.0" },
+    "version":   "1.0.0-rc.1",
+  "files": ["bindings_bg.wasm", "bindings.js"]
+}
+"#
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join("pyproject.toml")).unwrap(),
+        r#"[project]
+name = "bindings"
+version = "1.0.0rc1"  # bumped by the release script
+requires-python = ">=3.8"
+"#
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
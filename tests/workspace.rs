@@ -24,3 +24,161 @@ fn workspace_default_members() {
         .success()
         .stdout(predicate::eq(b"pkg2\n" as &[u8]));
 }
+
+#[test]
+fn workspace_from_member() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/workspace/pkg1").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("workspace.package.version")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"1.2.3\n" as &[u8]));
+}
+
+#[test]
+fn workspace_root_from_member() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/workspace/pkg1").unwrap();
+    let root = std::fs::canonicalize("tests/data/workspace").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("workspace.root")
+        .assert()
+        .success()
+        .stdout(predicate::eq(format!("{}\n", root.display()).into_bytes()));
+}
+
+#[test]
+fn workspace_from_package_workspace_key() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/workspace_hint/member").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("workspace.package.version")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"2.0.0\n" as &[u8]));
+}
+
+#[test]
+fn workspace_dependencies() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/workspace/pkg2").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("workspace.dependencies")
+        .arg("--delimiter=LF")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"log 0.4\npkg1 path=pkg1\nregex 1\nserde 1 features=derive\n" as &[u8],
+        ));
+}
+
+#[test]
+fn workspace_dependencies_usage() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/workspace").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("workspace.dependencies")
+        .arg("--usage")
+        .arg("--json")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"{\"log\":[\"pkg1\"],\"pkg1\":[\"pkg2\"],\"regex\":[],\"serde\":[\"pkg2\"]}\n"
+                as &[u8],
+        ));
+}
+
+#[test]
+fn workspace_dependencies_unused() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/workspace").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("workspace.dependencies")
+        .arg("--unused")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"regex\n" as &[u8]));
+}
+
+#[test]
+fn workspace_lints() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/workspace/pkg1").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("workspace.lints")
+        .arg("--delimiter=LF")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"clippy.all = { level = \"deny\", priority = -1 }\nclippy.unwrap_used = \"warn\"\nrust.unsafe_code = \"forbid\"\n"
+                as &[u8],
+        ));
+}
+
+#[test]
+fn workspace_metadata_key() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/workspace").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("workspace.metadata")
+        .arg("release.tag-prefix")
+        .assert()
+        .success()
+        .stdout(predicate::eq(b"v\n" as &[u8]));
+}
+
+#[test]
+fn workspace_graph_publish_order() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/graph/crates/app").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("workspace.graph")
+        .arg("--publish-order")
+        .arg("--delimiter=LF")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"graph-build-helper\ngraph-core\ngraph-macros\n" as &[u8],
+        ));
+}
+
+#[test]
+fn workspace_graph_edges() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/graph").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("workspace.graph")
+        .arg("--delimiter=LF")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"graph-app -> graph-build-helper (build)\ngraph-app -> graph-core\ngraph-app -> graph-macros\ngraph-core -> graph-testkit (dev)\ngraph-testkit -> graph-core\n"
+                as &[u8],
+        ));
+}
+
+#[test]
+fn workspace_graph_dev_cycle() {
+    let mut cmd = Command::cargo_bin("cargo-get").unwrap();
+    let p = std::fs::canonicalize("tests/data/graph").unwrap();
+    cmd.current_dir(p);
+
+    cmd.arg("workspace.graph")
+        .arg("--cycles")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            b"graph-core -> graph-testkit -> graph-core (dev)\n" as &[u8],
+        ));
+}